@@ -4,7 +4,6 @@ use libharu_ng::{libharu_version, prelude::*};
 ///
 /// This example will create a new PDF file named `hello_world.pdf` in the current directory.
 ///
-
 fn main() -> Result<(), HaruError> {
     println!("libharu version: {}", libharu_version());
 
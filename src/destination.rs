@@ -0,0 +1,116 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! The destination struct and related functions.
+//!
+//! A destination is created from a page using PdfPage::create_destination()
+//! and can be used as the open action of the document or as the target of
+//! an outline.
+//!
+//! ## Implementation status:
+//!
+//! - [x] HPDF_Destination_SetXYZ()
+//! - [x] HPDF_Destination_SetFit()
+//! - [x] HPDF_Destination_SetFitH()
+//! - [x] HPDF_Destination_SetFitV()
+//! - [x] HPDF_Destination_SetFitR()
+//! - [x] HPDF_Destination_SetFitB()
+//! - [x] HPDF_Destination_SetFitBH()
+//! - [x] HPDF_Destination_SetFitBV()
+//!
+
+use crate::{
+    document::PdfDocument,
+    haru_bindings as hb,
+    haru_types::{Destination, HaruError},
+};
+
+/// A destination on a page.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct PdfDestination<'doc> {
+    /// The reference to the haru destination.
+    pub(crate) dst: hb::HPDF_Destination,
+    /// The document owning the destination.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+impl PdfDestination<'_> {
+    /// Builds the error for a failed call from the destination's error record.
+    fn error(&self, api: &'static str, status: hb::HPDF_STATUS) -> HaruError {
+        HaruError::from_error_rec(api, status, unsafe { (*self.dst).error })
+    }
+
+    /// set() changes how the page is displayed when the destination is opened.
+    ///
+    /// API: HPDF_Destination_SetXYZ, HPDF_Destination_SetFit, HPDF_Destination_SetFitH,
+    /// HPDF_Destination_SetFitV, HPDF_Destination_SetFitR, HPDF_Destination_SetFitB,
+    /// HPDF_Destination_SetFitBH, HPDF_Destination_SetFitBV
+    ///
+    pub fn set(&self, destination: Destination) -> Result<&Self, HaruError> {
+        let (api, result) = unsafe {
+            match destination {
+                Destination::Xyz { left, top, zoom } => (
+                    "HPDF_Destination_SetXYZ",
+                    hb::HPDF_Destination_SetXYZ(self.dst, left, top, zoom),
+                ),
+                Destination::Fit => (
+                    "HPDF_Destination_SetFit",
+                    hb::HPDF_Destination_SetFit(self.dst),
+                ),
+                Destination::FitH { top } => (
+                    "HPDF_Destination_SetFitH",
+                    hb::HPDF_Destination_SetFitH(self.dst, top),
+                ),
+                Destination::FitV { left } => (
+                    "HPDF_Destination_SetFitV",
+                    hb::HPDF_Destination_SetFitV(self.dst, left),
+                ),
+                Destination::FitR {
+                    left,
+                    bottom,
+                    right,
+                    top,
+                } => (
+                    "HPDF_Destination_SetFitR",
+                    hb::HPDF_Destination_SetFitR(self.dst, left, bottom, right, top),
+                ),
+                Destination::FitB => (
+                    "HPDF_Destination_SetFitB",
+                    hb::HPDF_Destination_SetFitB(self.dst),
+                ),
+                Destination::FitBH { top } => (
+                    "HPDF_Destination_SetFitBH",
+                    hb::HPDF_Destination_SetFitBH(self.dst, top),
+                ),
+                Destination::FitBV { left } => (
+                    "HPDF_Destination_SetFitBV",
+                    hb::HPDF_Destination_SetFitBV(self.dst, left),
+                ),
+            }
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error(api, result)),
+        }
+    }
+}
@@ -29,20 +29,80 @@
 //! - [ ] HPDF_Encoder_GetWritingMode()
 //!
 
+use std::{
+    cell::{Cell, RefCell},
+    io::Write,
+};
+
 use crate::{
-    font::PdfFont,
+    destination::PdfDestination,
+    embedded_file::PdfEmbeddedFile,
+    ext_gstate::PdfExtGState,
+    font::{self, PdfFont},
     haru_bindings as hb,
-    haru_types::{CompressionMode, HaruError, PageLayout, PageMode},
+    haru_types::{
+        CcittEncoding, ColorSpace, CompressionMode, Destination, EncryptionMode, HaruError,
+        InfoAttr, PageDirection, PageLayout, PageMode, PageNumStyle, PageSize, PdfAConformance,
+        PdfDate, Permissions, ViewerPreferences,
+    },
+    icc_profile::PdfIccProfile,
     image::PdfImage,
-    page::PdfPage,
+    outline::PdfOutline,
+    page::{Pages, PdfPage},
 };
 
+/// The closure type used by with_error_handler().
+type ErrorHandler = Box<dyn FnMut(&HaruError)>;
+
+/// State shared with libharu's error callback through the `user_data` pointer.
+///
+struct ErrorState {
+    /// The last error reported by libharu.
+    last_error: RefCell<Option<HaruError>>,
+    /// The user's error handler, if any.
+    handler: RefCell<Option<ErrorHandler>>,
+}
+
+/// Error callback passed to HPDF_New(). It records the error and forwards it
+/// to the user's handler.
+///
+unsafe extern "C" fn error_trampoline(
+    error_no: hb::HPDF_STATUS,
+    detail_no: hb::HPDF_STATUS,
+    user_data: *mut std::ffi::c_void,
+) {
+    let state = unsafe { &*(user_data as *const ErrorState) };
+    let error = HaruError::new(error_no, detail_no);
+    // A panic must not unwind into libharu, so skip borrows that are taken.
+    if let Ok(mut handler) = state.handler.try_borrow_mut() {
+        if let Some(handler) = handler.as_mut() {
+            handler(&error);
+        }
+    }
+    if let Ok(mut last_error) = state.last_error.try_borrow_mut() {
+        *last_error = Some(error);
+    }
+}
+
 /// The PDF document.
 ///
-#[derive(Debug)]
 pub struct PdfDocument {
     /// The reference to the haru document.
-    pub doc: hb::HPDF_Doc,
+    pub(crate) doc: hb::HPDF_Doc,
+    /// The error state handed to libharu, allocated with `Box::into_raw()`
+    /// and freed in `drop()` after `doc`.
+    error_state: *mut ErrorState,
+    /// The PDF/A conformance level set by enable_pdfa().
+    pdfa: Cell<Option<PdfAConformance>>,
+}
+
+impl std::fmt::Debug for PdfDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PdfDocument")
+            .field("doc", &self.doc)
+            .field("last_error", &self.last_error())
+            .finish()
+    }
 }
 
 impl Default for PdfDocument {
@@ -57,8 +117,101 @@ impl PdfDocument {
     /// API: HPDF_New
     ///
     pub fn new() -> Self {
-        let doc = unsafe { hb::HPDF_New(None, core::ptr::null_mut()) };
-        Self { doc }
+        Self::new_with_state(None)
+    }
+
+    /// Create a document which calls `handler` whenever libharu reports an error,
+    /// e.g. to log the error and detail codes as they happen. The handler must
+    /// not panic.
+    ///
+    /// API: HPDF_New
+    ///
+    pub fn with_error_handler<F>(handler: F) -> Self
+    where
+        F: FnMut(&HaruError) + 'static,
+    {
+        Self::new_with_state(Some(Box::new(handler)))
+    }
+
+    fn new_with_state(handler: Option<ErrorHandler>) -> Self {
+        let error_state = Box::into_raw(Box::new(ErrorState {
+            last_error: RefCell::new(None),
+            handler: RefCell::new(handler),
+        }));
+        let doc = unsafe { hb::HPDF_New(Some(error_trampoline), error_state as *mut _) };
+        Self {
+            doc,
+            error_state,
+            pdfa: Cell::new(None),
+        }
+    }
+
+    /// Builds the error for a failed call from the document's error record.
+    pub(crate) fn error(&self, api: &'static str, status: hb::HPDF_STATUS) -> HaruError {
+        HaruError::from_error_rec(api, status, unsafe { &mut (*self.doc).error })
+    }
+
+    /// Turns the status of a call to `api` into a result.
+    pub(crate) fn check(
+        &self,
+        api: &'static str,
+        status: hb::HPDF_STATUS,
+    ) -> Result<(), HaruError> {
+        match status {
+            0 => Ok(()),
+            _ => Err(self.error(api, status)),
+        }
+    }
+
+    /// Returns an error with the given code unless `other` is this document.
+    /// Handles of one document must not be used with another one.
+    pub(crate) fn ensure_same(
+        &self,
+        other: &PdfDocument,
+        api: &'static str,
+        error_no: u32,
+    ) -> Result<(), HaruError> {
+        match std::ptr::eq(self, other) {
+            true => Ok(()),
+            false => Err(HaruError::from(error_no as u64).with_api(api)),
+        }
+    }
+
+    /// last_error() returns the last error libharu reported for this document,
+    /// including its detail code.
+    ///
+    pub fn last_error(&self) -> Option<HaruError> {
+        let state = unsafe { &*self.error_state };
+        state.last_error.borrow().clone()
+    }
+
+    /// Runs `f` with the pending error of the document put aside, as libharu
+    /// refuses page operators after an error. The page guards use this to
+    /// clean up after a failed operator.
+    ///
+    /// The error record is shared by the document and its pages, so it is
+    /// cleared and set again through libharu's own error functions. Errors
+    /// raised by `f` still reach the error handler, but the pending error is
+    /// put back afterwards, also as last_error(). It still has to be reset
+    /// before saving.
+    pub(crate) fn with_error_put_aside<T>(&self, f: impl FnOnce() -> T) -> T {
+        unsafe {
+            let error = std::ptr::addr_of_mut!((*self.doc).error);
+            let error_no = hb::HPDF_Error_GetCode(error);
+            if error_no == 0 {
+                return f();
+            }
+            let detail_no = hb::HPDF_Error_GetDetailCode(error);
+            let last_error = self.last_error();
+
+            hb::HPDF_Error_Reset(error);
+            let result = f();
+            hb::HPDF_SetError(error, error_no, detail_no);
+            if let Ok(mut last) = (*self.error_state).last_error.try_borrow_mut() {
+                *last = last_error;
+            }
+            result
+        }
     }
 
     /// set_page_mode() sets how the document should be displayed.
@@ -69,7 +222,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_SetPageMode(self.doc, mode.to_hpdf_mode()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetPageMode", result)),
         }
     }
 
@@ -90,17 +243,15 @@ impl PdfDocument {
         &self,
         fontname: &str,
         encoding_name: Option<&str>,
-    ) -> Result<PdfFont, HaruError> {
+    ) -> Result<PdfFont<'_>, HaruError> {
         let fontname = std::ffi::CString::new(fontname).unwrap();
 
         // If encoding_name is specified, the font with the specified encoding is returned.
         // Otherwise, pass NULL for encoding_name to get the font with the default encoding.
-        if let Some(encoding_name) = encoding_name {
-            let encoding_name = std::ffi::CString::new(encoding_name).unwrap();
-            let font =
-                unsafe { hb::HPDF_GetFont(self.doc, fontname.as_ptr(), encoding_name.as_ptr()) };
-            return Ok(PdfFont { font_ref: font });
-        }
+        let encoding_name = encoding_name.map(|name| std::ffi::CString::new(name).unwrap());
+        let encoding_ptr = encoding_name
+            .as_ref()
+            .map_or(core::ptr::null(), |name| name.as_ptr());
 
         // When this fails, it returns NULL.
         // One of these error codes might be the culprit:
@@ -109,11 +260,26 @@ impl PdfDocument {
         // - HPDF_INVALID_FONT_NAME - An invalid font name was set.
         // - HPDF_INVALID_ENCODING_NAME - An invalid encoding name was set.
         // - HPDF_UNSUPPORTED_FONT_TYPE - An unsupported font type was set.
-        let font = unsafe { hb::HPDF_GetFont(self.doc, fontname.as_ptr(), core::ptr::null_mut()) };
-        match font.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfFont { font_ref: font }),
+        // PDF/A fonts are checked before libharu adds the font to the document.
+        // Fonts without a loaded definition are standard fonts, or unknown.
+        if self.pdfa.get().is_some() {
+            let fontdef = unsafe { hb::HPDF_Doc_FindFontDef(self.doc, fontname.as_ptr()) };
+            if fontdef.is_null() || unsafe { !font::is_fontdef_embedded(fontdef) } {
+                return Err(
+                    HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api("HPDF_GetFont")
+                );
+            }
+        }
+
+        let font = unsafe { hb::HPDF_GetFont(self.doc, fontname.as_ptr(), encoding_ptr) };
+        if font.is_null() {
+            return Err(self.error("HPDF_GetFont", 0));
         }
+
+        Ok(PdfFont {
+            font_ref: font,
+            doc: self,
+        })
     }
 
     /// set_page_layout() sets how the page should be displayed. If this attribute
@@ -125,7 +291,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_SetPageLayout(self.doc, layout.to_hpdf_layout()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetPageLayout", result)),
         }
     }
 
@@ -138,77 +304,1027 @@ impl PdfDocument {
         PageLayout::from_hpdf_layout(layout)
     }
 
+    /// set_viewer_preference() sets how the viewer's user interface is
+    /// displayed when the document is opened. This raises the PDF version to 1.6.
+    ///
+    /// API: HPDF_SetViewerPreference
+    ///
+    pub fn set_viewer_preference(&self, prefs: ViewerPreferences) -> Result<&Self, HaruError> {
+        let result =
+            unsafe { hb::HPDF_SetViewerPreference(self.doc, prefs.to_hpdf_viewer_preference()) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_SetViewerPreference", result)),
+        }
+    }
+
+    /// get_viewer_preference() returns the current viewer preferences.
+    ///
+    /// libharu does not report PRINT_SCALING_NONE, so the PrintScaling entry
+    /// is looked up in the catalog's ViewerPreferences dictionary directly.
+    ///
+    /// API: HPDF_GetViewerPreference
+    ///
+    pub fn get_viewer_preference(&self) -> ViewerPreferences {
+        let mut prefs = unsafe { hb::HPDF_GetViewerPreference(self.doc) };
+
+        let prefs_key = std::ffi::CString::new("ViewerPreferences").unwrap();
+        let scaling_key = std::ffi::CString::new("PrintScaling").unwrap();
+        let print_scaling_none = unsafe {
+            let dict = hb::HPDF_Dict_GetItem(
+                (*self.doc).catalog,
+                prefs_key.as_ptr(),
+                hb::HPDF_OCLASS_DICT as hb::HPDF_UINT16,
+            );
+            let name = if dict.is_null() {
+                std::ptr::null_mut()
+            } else {
+                hb::HPDF_Dict_GetItem(
+                    dict as hb::HPDF_Dict,
+                    scaling_key.as_ptr(),
+                    hb::HPDF_OCLASS_NAME as hb::HPDF_UINT16,
+                )
+            };
+            !name.is_null()
+                && std::ffi::CStr::from_ptr(hb::HPDF_Name_GetValue(name as hb::HPDF_Name))
+                    .to_bytes()
+                    == b"None"
+        };
+        if print_scaling_none {
+            prefs |= hb::HPDF_PRINT_SCALING_NONE;
+        }
+
+        ViewerPreferences::from_hpdf_viewer_preference(prefs)
+    }
+
+    /// attach_file() embeds the file at the given path into the document.
+    /// The file is read when the document is saved.
+    ///
+    /// API: HPDF_AttachFile
+    ///
+    pub fn attach_file(&self, path: &str) -> Result<PdfEmbeddedFile<'_>, HaruError> {
+        self.ensure_not_pdfa("HPDF_AttachFile")?;
+        let path = std::ffi::CString::new(path).unwrap();
+        if unsafe { self.has_embedded_file(&path) } {
+            return Err(
+                HaruError::from(hb::HPDF_DUPLICATE_REGISTRATION as u64).with_api("HPDF_AttachFile")
+            );
+        }
+
+        let file = unsafe { hb::HPDF_AttachFile(self.doc, path.as_ptr()) };
+        if file.is_null() {
+            return Err(self.error("HPDF_AttachFile", 0));
+        }
+
+        // libharu marks the file specification with /Type /F instead of /Filespec.
+        let type_key = std::ffi::CString::new("Type").unwrap();
+        let filespec = std::ffi::CString::new("Filespec").unwrap();
+        let result = unsafe { hb::HPDF_Dict_AddName(file, type_key.as_ptr(), filespec.as_ptr()) };
+        match result {
+            0 => Ok(PdfEmbeddedFile { file, doc: self }),
+            _ => Err(self.error("HPDF_Dict_AddName", result)),
+        }
+    }
+
+    /// attach_bytes() embeds in-memory data as a file with the given name
+    /// and MIME type (e.g. "text/xml") into the document. File names must be
+    /// unique within the document.
+    ///
+    pub fn attach_bytes(
+        &self,
+        name: &str,
+        mime: &str,
+        data: &[u8],
+    ) -> Result<PdfEmbeddedFile<'_>, HaruError> {
+        self.ensure_not_pdfa("HPDF_AttachFile")?;
+
+        let key = |key: &str| std::ffi::CString::new(key).unwrap();
+        // The Unicode file name is a UTF-16BE text string with a byte order mark.
+        let mut unicode_name = vec![0xFE, 0xFF];
+        unicode_name.extend(name.encode_utf16().flat_map(u16::to_be_bytes));
+        let name = std::ffi::CString::new(name).unwrap();
+        let mime = std::ffi::CString::new(mime).unwrap();
+
+        let (Ok(size), Ok(len), Ok(unicode_len)) = (
+            i32::try_from(data.len()),
+            u32::try_from(data.len()),
+            u32::try_from(unicode_name.len()),
+        ) else {
+            return Err(
+                HaruError::from(hb::HPDF_INVALID_PARAMETER as u64).with_api("HPDF_AttachFile")
+            );
+        };
+        if unsafe { self.has_embedded_file(&name) } {
+            return Err(
+                HaruError::from(hb::HPDF_DUPLICATE_REGISTRATION as u64).with_api("HPDF_AttachFile")
+            );
+        }
+
+        unsafe {
+            let mmgr = (*self.doc).mmgr;
+            let xref = (*self.doc).xref;
+
+            // The stream holding the file contents.
+            let stream = hb::HPDF_DictStream_New(mmgr, xref);
+            if stream.is_null() {
+                return Err(self.error("HPDF_DictStream_New", 0));
+            }
+            (*stream).filter = hb::HPDF_STREAM_FILTER_FLATE_DECODE;
+
+            let params = hb::HPDF_Dict_New(mmgr);
+            if params.is_null() {
+                return Err(self.error("HPDF_Dict_New", 0));
+            }
+
+            self.check(
+                "HPDF_Dict_AddName",
+                hb::HPDF_Dict_AddName(stream, key("Type").as_ptr(), key("EmbeddedFile").as_ptr()),
+            )?;
+            self.check(
+                "HPDF_Dict_AddName",
+                hb::HPDF_Dict_AddName(stream, key("Subtype").as_ptr(), mime.as_ptr()),
+            )?;
+            self.check(
+                "HPDF_Dict_AddNumber",
+                hb::HPDF_Dict_AddNumber(params, key("Size").as_ptr(), size),
+            )?;
+            self.check(
+                "HPDF_Dict_Add",
+                hb::HPDF_Dict_Add(stream, key("Params").as_ptr(), params as *mut _),
+            )?;
+            self.check(
+                "HPDF_Stream_Write",
+                hb::HPDF_Stream_Write((*stream).stream, data.as_ptr(), len),
+            )?;
+
+            // The file specification referring to the stream.
+            let file = hb::HPDF_Dict_New(mmgr);
+            if file.is_null() {
+                return Err(self.error("HPDF_Dict_New", 0));
+            }
+            self.check("HPDF_Xref_Add", hb::HPDF_Xref_Add(xref, file as *mut _))?;
+            let ef = hb::HPDF_Dict_New(mmgr);
+            if ef.is_null() {
+                return Err(self.error("HPDF_Dict_New", 0));
+            }
+            let file_name = hb::HPDF_String_New(mmgr, name.as_ptr(), std::ptr::null_mut());
+            if file_name.is_null() {
+                return Err(self.error("HPDF_String_New", 0));
+            }
+            let unicode_name = hb::HPDF_Binary_New(mmgr, unicode_name.as_mut_ptr(), unicode_len);
+            if unicode_name.is_null() {
+                return Err(self.error("HPDF_Binary_New", 0));
+            }
+
+            self.check(
+                "HPDF_Dict_AddName",
+                hb::HPDF_Dict_AddName(file, key("Type").as_ptr(), key("Filespec").as_ptr()),
+            )?;
+            self.check(
+                "HPDF_Dict_Add",
+                hb::HPDF_Dict_Add(file, key("F").as_ptr(), file_name as *mut _),
+            )?;
+            self.check(
+                "HPDF_Dict_Add",
+                hb::HPDF_Dict_Add(file, key("UF").as_ptr(), unicode_name as *mut _),
+            )?;
+            self.check(
+                "HPDF_Dict_Add",
+                hb::HPDF_Dict_Add(file, key("EF").as_ptr(), ef as *mut _),
+            )?;
+            self.check(
+                "HPDF_Dict_Add",
+                hb::HPDF_Dict_Add(ef, key("F").as_ptr(), stream as *mut _),
+            )?;
+
+            self.add_embedded_file(name.as_ptr(), file)?;
+            Ok(PdfEmbeddedFile { file, doc: self })
+        }
+    }
+
+    /// Returns the EmbeddedFiles name tree of the catalog, if there is one.
+    unsafe fn embedded_files(&self) -> Option<hb::HPDF_Dict> {
+        let key = std::ffi::CString::new("EmbeddedFiles").unwrap();
+        let names = hb::HPDF_Catalog_GetNames((*self.doc).catalog);
+        if names.is_null() {
+            return None;
+        }
+        let tree = hb::HPDF_Dict_GetItem(names, key.as_ptr(), hb::HPDF_OCLASS_DICT as u16);
+        (!tree.is_null()).then_some(tree as hb::HPDF_Dict)
+    }
+
+    /// Returns true if a file with the given name is embedded already.
+    unsafe fn has_embedded_file(&self, name: &std::ffi::CStr) -> bool {
+        let Some(tree) = self.embedded_files() else {
+            return false;
+        };
+        let key = std::ffi::CString::new("Names").unwrap();
+        let items = hb::HPDF_Dict_GetItem(tree, key.as_ptr(), hb::HPDF_OCLASS_ARRAY as u16)
+            as hb::HPDF_Array;
+        if items.is_null() {
+            return false;
+        }
+
+        let string_class = hb::HPDF_OCLASS_STRING as hb::HPDF_UINT16;
+        (0..hb::HPDF_Array_Items(items)).step_by(2).any(|i| {
+            let elem = hb::HPDF_Array_GetItem(items, i, string_class) as hb::HPDF_String;
+            !elem.is_null()
+                && std::slice::from_raw_parts((*elem).value, (*elem).len as usize)
+                    == name.to_bytes()
+        })
+    }
+
+    /// Adds a file specification to the EmbeddedFiles name tree of the
+    /// catalog, keeping the names sorted like HPDF_AttachFile does.
+    unsafe fn add_embedded_file(
+        &self,
+        name: *const std::os::raw::c_char,
+        file: hb::HPDF_Dict,
+    ) -> Result<(), HaruError> {
+        let key = |key: &str| std::ffi::CString::new(key).unwrap();
+        let mmgr = (*self.doc).mmgr;
+        let xref = (*self.doc).xref;
+
+        let tree = match self.embedded_files() {
+            Some(tree) => tree,
+            None => {
+                let mut names = hb::HPDF_Catalog_GetNames((*self.doc).catalog);
+                if names.is_null() {
+                    names = hb::HPDF_Dict_New(mmgr);
+                    if names.is_null() {
+                        return Err(self.error("HPDF_Dict_New", 0));
+                    }
+                    self.check("HPDF_Xref_Add", hb::HPDF_Xref_Add(xref, names as *mut _))?;
+                    (*names).header.obj_class |= hb::HPDF_OSUBCLASS_NAMEDICT as hb::HPDF_UINT16;
+                    self.check(
+                        "HPDF_Catalog_SetNames",
+                        hb::HPDF_Catalog_SetNames((*self.doc).catalog, names),
+                    )?;
+                }
+
+                let tree = hb::HPDF_Dict_New(mmgr);
+                if tree.is_null() {
+                    return Err(self.error("HPDF_Dict_New", 0));
+                }
+                self.check("HPDF_Xref_Add", hb::HPDF_Xref_Add(xref, tree as *mut _))?;
+                (*tree).header.obj_class |= hb::HPDF_OSUBCLASS_NAMETREE as hb::HPDF_UINT16;
+                let items = hb::HPDF_Array_New(mmgr);
+                if items.is_null() {
+                    return Err(self.error("HPDF_Array_New", 0));
+                }
+                self.check(
+                    "HPDF_Dict_Add",
+                    hb::HPDF_Dict_Add(tree, key("Names").as_ptr(), items as *mut _),
+                )?;
+                self.check(
+                    "HPDF_Dict_Add",
+                    hb::HPDF_Dict_Add(names, key("EmbeddedFiles").as_ptr(), tree as *mut _),
+                )?;
+                tree
+            }
+        };
+
+        let items = hb::HPDF_Dict_GetItem(
+            tree,
+            key("Names").as_ptr(),
+            hb::HPDF_OCLASS_ARRAY as hb::HPDF_UINT16,
+        ) as hb::HPDF_Array;
+        if items.is_null() {
+            return Err(self.error("HPDF_Dict_GetItem", 0));
+        }
+        let name = hb::HPDF_String_New(mmgr, name, std::ptr::null_mut());
+        if name.is_null() {
+            return Err(self.error("HPDF_String_New", 0));
+        }
+
+        // The keys of a name tree have to be sorted in lexical order.
+        let string_class = hb::HPDF_OCLASS_STRING as hb::HPDF_UINT16;
+        for i in (0..hb::HPDF_Array_Items(items)).step_by(2) {
+            let elem = hb::HPDF_Array_GetItem(items, i, string_class) as hb::HPDF_String;
+            if !elem.is_null() && hb::HPDF_String_Cmp(name, elem) < 0 {
+                self.check(
+                    "HPDF_Array_Insert",
+                    hb::HPDF_Array_Insert(items, elem as *mut _, name as *mut _),
+                )?;
+                return self.check(
+                    "HPDF_Array_Insert",
+                    hb::HPDF_Array_Insert(items, elem as *mut _, file as *mut _),
+                );
+            }
+        }
+
+        self.check("HPDF_Array_Add", hb::HPDF_Array_Add(items, name as *mut _))?;
+        self.check("HPDF_Array_Add", hb::HPDF_Array_Add(items, file as *mut _))
+    }
+
+    /// enable_pdfa() makes the document conform to PDF/A, with the given ICC
+    /// profile as its output intent, e.g. "sRGB IEC61966-2.1". It adds a
+    /// document ID right away; the XMP metadata is generated from the info
+    /// dictionary when the document is first saved. If no info attribute is
+    /// set by then, the CreationDate is set to the current time.
+    ///
+    /// PDF/A can't be enabled for documents which already contain what it
+    /// forbids, and from now on such operations are refused with
+    /// InvalidOperation: encryption, fonts that are not embedded, attached
+    /// files, transparency and soft masks.
+    ///
+    /// API: HPDF_PDFA_GenerateID, HPDF_PDFA_AppendOutputIntents,
+    /// HPDF_PDFA_SetPDFAConformance
+    ///
+    pub fn enable_pdfa(
+        &self,
+        conformance: PdfAConformance,
+        output_intent: &str,
+        profile: &PdfIccProfile<'_>,
+    ) -> Result<&Self, HaruError> {
+        const API: &str = "HPDF_PDFA_SetPDFAConformance";
+        self.ensure_same(profile.doc, API, hb::HPDF_INVALID_OBJECT)?;
+        if unsafe { !self.conforms_to_pdfa() } {
+            return Err(HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api(API));
+        }
+
+        let result = unsafe { hb::HPDF_PDFA_GenerateID(self.doc) };
+        if result != 0 {
+            return Err(self.error("HPDF_PDFA_GenerateID", result));
+        }
+        self.add_output_intent(output_intent, profile)?;
+        self.pdfa.set(Some(conformance));
+        Ok(self)
+    }
+
+    /// get_pdfa() returns the PDF/A conformance level set by enable_pdfa().
+    ///
+    pub fn get_pdfa(&self) -> Option<PdfAConformance> {
+        self.pdfa.get()
+    }
+
+    /// add_output_intent() adds an ICC profile as an output intent of the
+    /// document under the given name, e.g. "sRGB IEC61966-2.1".
+    ///
+    /// API: HPDF_PDFA_AppendOutputIntents
+    ///
+    pub fn add_output_intent(
+        &self,
+        name: &str,
+        profile: &PdfIccProfile<'_>,
+    ) -> Result<&Self, HaruError> {
+        self.ensure_same(
+            profile.doc,
+            "HPDF_PDFA_AppendOutputIntents",
+            hb::HPDF_INVALID_OBJECT,
+        )?;
+        let name = std::ffi::CString::new(name).unwrap();
+        let result =
+            unsafe { hb::HPDF_PDFA_AppendOutputIntents(self.doc, name.as_ptr(), profile.icc) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_PDFA_AppendOutputIntents", result)),
+        }
+    }
+
+    /// create_ext_gstate() creates a new extended graphics state, e.g. to
+    /// paint semi-transparent.
+    ///
+    /// API: HPDF_CreateExtGState
+    ///
+    pub fn create_ext_gstate(&self) -> Result<PdfExtGState<'_>, HaruError> {
+        let gstate = unsafe { hb::HPDF_CreateExtGState(self.doc) };
+        match gstate.is_null() {
+            true => Err(self.error("HPDF_CreateExtGState", 0)),
+            false => Ok(PdfExtGState { gstate, doc: self }),
+        }
+    }
+
+    /// load_icc_profile_from_file() loads an ICC profile with the given number
+    /// of color components (1, 3 or 4).
+    ///
+    /// API: HPDF_LoadIccProfileFromFile
+    ///
+    pub fn load_icc_profile_from_file(
+        &self,
+        filename: &str,
+        num_components: u32,
+    ) -> Result<PdfIccProfile<'_>, HaruError> {
+        Self::ensure_icc_components(num_components, "HPDF_LoadIccProfileFromFile")?;
+        let filename = std::ffi::CString::new(filename).unwrap();
+        let icc = unsafe {
+            hb::HPDF_LoadIccProfileFromFile(self.doc, filename.as_ptr(), num_components as i32)
+        };
+        match icc.is_null() {
+            true => Err(self.error("HPDF_LoadIccProfileFromFile", 0)),
+            false => Ok(PdfIccProfile {
+                icc,
+                num_components,
+                doc: self,
+            }),
+        }
+    }
+
+    /// load_icc_profile_from_bytes() loads an ICC profile with the given
+    /// number of color components (1, 3 or 4) from memory.
+    ///
+    /// API: HPDF_ICC_LoadIccFromMem
+    ///
+    pub fn load_icc_profile_from_bytes(
+        &self,
+        data: &[u8],
+        num_components: u32,
+    ) -> Result<PdfIccProfile<'_>, HaruError> {
+        Self::ensure_icc_components(num_components, "HPDF_ICC_LoadIccFromMem")?;
+        let Ok(len) = u32::try_from(data.len()) else {
+            return Err(HaruError::from(hb::HPDF_INVALID_PARAMETER as u64)
+                .with_api("HPDF_ICC_LoadIccFromMem"));
+        };
+        let icc = unsafe {
+            let mmgr = (*self.doc).mmgr;
+            let stream = hb::HPDF_MemStream_New(mmgr, 4096);
+            if stream.is_null() {
+                return Err(self.error("HPDF_MemStream_New", 0));
+            }
+            let result = hb::HPDF_Stream_Write(stream, data.as_ptr(), len);
+            let icc = match result {
+                0 => hb::HPDF_ICC_LoadIccFromMem(
+                    self.doc,
+                    mmgr,
+                    stream,
+                    (*self.doc).xref,
+                    num_components as i32,
+                ),
+                _ => std::ptr::null_mut(),
+            };
+            hb::HPDF_Stream_Free(stream);
+            icc
+        };
+        match icc.is_null() {
+            true => Err(self.error("HPDF_ICC_LoadIccFromMem", 0)),
+            false => Ok(PdfIccProfile {
+                icc,
+                num_components,
+                doc: self,
+            }),
+        }
+    }
+
+    /// Checks the number of ICC color components up front: libharu frees the
+    /// profile twice when it rejects the number itself.
+    fn ensure_icc_components(num_components: u32, api: &'static str) -> Result<(), HaruError> {
+        match num_components {
+            1 | 3 | 4 => Ok(()),
+            _ => Err(HaruError::from(hb::HPDF_INVALID_ICC_COMPONENT_NUM as u64).with_api(api)),
+        }
+    }
+
+    /// Refuses operations that are not allowed in PDF/A documents.
+    pub(crate) fn ensure_not_pdfa(&self, api: &'static str) -> Result<(), HaruError> {
+        match self.pdfa.get() {
+            None => Ok(()),
+            Some(_) => Err(HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api(api)),
+        }
+    }
+
+    /// Refuses fonts that are not embedded in PDF/A documents.
+    pub(crate) fn ensure_pdfa_font(
+        &self,
+        font: &PdfFont<'_>,
+        api: &'static str,
+    ) -> Result<(), HaruError> {
+        match font.is_embedded() {
+            true => Ok(()),
+            false => self.ensure_not_pdfa(api),
+        }
+    }
+
+    /// Returns true if the document contains nothing PDF/A-1 forbids:
+    /// encryption, fonts that are not embedded, attached files, transparent
+    /// extended graphics states or soft masks.
+    unsafe fn conforms_to_pdfa(&self) -> bool {
+        if (*self.doc).encrypt_on != 0 {
+            return false;
+        }
+        if let Some(tree) = self.embedded_files() {
+            let names = std::ffi::CString::new("Names").unwrap();
+            let items = hb::HPDF_Dict_GetItem(tree, names.as_ptr(), hb::HPDF_OCLASS_ARRAY as u16);
+            if !items.is_null() && hb::HPDF_Array_Items(items as hb::HPDF_Array) > 0 {
+                return false;
+            }
+        }
+
+        let item = |dict: hb::HPDF_Dict, key: &str, class: u32| {
+            let key = std::ffi::CString::new(key).unwrap();
+            hb::HPDF_Dict_GetItem(dict, key.as_ptr(), class as hb::HPDF_UINT16)
+        };
+        let entries = (*(*self.doc).xref).entries;
+        (0..(*entries).count).all(|i| {
+            let entry = hb::HPDF_List_ItemAt(entries, i) as hb::HPDF_XrefEntry;
+            let obj = (*entry).obj as *mut hb::HPDF_Obj_Header;
+            if obj.is_null() || (*obj).obj_class as u32 & 0xFF != hb::HPDF_OCLASS_DICT {
+                return true;
+            }
+            let dict = obj as hb::HPDF_Dict;
+            match (*obj).obj_class as u32 & 0xFF00 {
+                hb::HPDF_OSUBCLASS_FONT => PdfFont {
+                    font_ref: dict,
+                    doc: self,
+                }
+                .is_embedded(),
+                hb::HPDF_OSUBCLASS_EXT_GSTATE | hb::HPDF_OSUBCLASS_EXT_GSTATE_R => {
+                    let opaque = |key| {
+                        let alpha = item(dict, key, hb::HPDF_OCLASS_REAL) as hb::HPDF_Real;
+                        alpha.is_null() || (*alpha).value >= 1.0
+                    };
+                    let blend = item(dict, "BM", hb::HPDF_OCLASS_NAME) as hb::HPDF_Name;
+                    opaque("ca")
+                        && opaque("CA")
+                        && (blend.is_null()
+                            || std::ffi::CStr::from_ptr((*blend).value.as_ptr()).to_bytes()
+                                == b"Normal")
+                }
+                hb::HPDF_OSUBCLASS_XOBJECT => item(dict, "SMask", hb::HPDF_OCLASS_DICT).is_null(),
+                _ => true,
+            }
+        })
+    }
+
+    /// Refuses to change the info dictionary of a PDF/A document once its
+    /// XMP metadata, which has to match it, is written.
+    fn ensure_info_writable(&self, api: &'static str) -> Result<(), HaruError> {
+        match self.pdfa.get().is_some() && unsafe { self.has_metadata() } {
+            false => Ok(()),
+            true => Err(HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api(api)),
+        }
+    }
+
+    /// Returns true if the catalog has XMP metadata.
+    unsafe fn has_metadata(&self) -> bool {
+        let key = std::ffi::CString::new("Metadata").unwrap();
+        let metadata = hb::HPDF_Dict_GetItem(
+            (*self.doc).catalog,
+            key.as_ptr(),
+            hb::HPDF_OCLASS_DICT as hb::HPDF_UINT16,
+        );
+        !metadata.is_null()
+    }
+
+    /// Validates a PDF/A document right before saving and generates its XMP
+    /// metadata on the first save.
+    fn write_pdfa_metadata(&self) -> Result<(), HaruError> {
+        const API: &str = "HPDF_PDFA_SetPDFAConformance";
+        let Some(conformance) = self.pdfa.get() else {
+            return Ok(());
+        };
+
+        let has_output_intent = unsafe {
+            let key = std::ffi::CString::new("OutputIntents").unwrap();
+            let intents = hb::HPDF_Dict_GetItem(
+                (*self.doc).catalog,
+                key.as_ptr(),
+                hb::HPDF_OCLASS_ARRAY as hb::HPDF_UINT16,
+            );
+            !intents.is_null() && hb::HPDF_Array_Items(intents as hb::HPDF_Array) > 0
+        };
+        if !has_output_intent || unsafe { !self.conforms_to_pdfa() } {
+            return Err(HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api(API));
+        }
+        // libharu would add another metadata stream on every save.
+        if unsafe { self.has_metadata() } {
+            return Ok(());
+        }
+
+        // libharu only writes the metadata if one of these attributes is set.
+        let has_info = [
+            InfoAttr::Title,
+            InfoAttr::Author,
+            InfoAttr::Subject,
+            InfoAttr::CreationDate,
+            InfoAttr::ModDate,
+            InfoAttr::Creator,
+            InfoAttr::Keywords,
+        ]
+        .into_iter()
+        .any(|attr| self.get_info(attr).is_some());
+        if !has_info {
+            self.set_info_date(InfoAttr::CreationDate, PdfDate::now())?;
+        }
+
+        let result =
+            unsafe { hb::HPDF_PDFA_SetPDFAConformance(self.doc, conformance.to_hpdf_pdfa_type()) };
+        match result {
+            0 => Ok(()),
+            _ => Err(self.error(API, result)),
+        }
+    }
+
     /// save_to_file() saves the PDF document to a file.
     ///
     pub fn save_to_file(&self, filename: &str) -> Result<&Self, HaruError> {
+        self.write_pdfa_metadata()?;
         let filename = std::ffi::CString::new(filename).unwrap();
         let result = unsafe { hb::HPDF_SaveToFile(self.doc, filename.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SaveToFile", result)),
         }
     }
 
+    /// save_to_bytes() saves the PDF document into memory and returns its contents.
+    ///
+    /// API: HPDF_SaveToStream, HPDF_GetStreamSize, HPDF_ReadFromStream
+    ///
+    pub fn save_to_bytes(&self) -> Result<Vec<u8>, HaruError> {
+        let mut buf = Vec::new();
+        self.save_to_writer(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// save_to_writer() saves the PDF document into libharu's temporary stream
+    /// and copies it to the given writer. The document never touches the filesystem.
+    ///
+    /// API: HPDF_SaveToStream, HPDF_GetStreamSize, HPDF_ResetStream, HPDF_ReadFromStream
+    ///
+    pub fn save_to_writer<W: Write>(&self, writer: &mut W) -> Result<&Self, HaruError> {
+        self.write_pdfa_metadata()?;
+        let result = unsafe { hb::HPDF_SaveToStream(self.doc) };
+        if result != 0 {
+            return Err(self.error("HPDF_SaveToStream", result));
+        }
+
+        let size = unsafe { hb::HPDF_GetStreamSize(self.doc) };
+        let result = unsafe { hb::HPDF_ResetStream(self.doc) };
+        if result != 0 {
+            return Err(self.error("HPDF_ResetStream", result));
+        }
+
+        // Copy the stream in chunks. HPDF_ReadFromStream() returns
+        // HPDF_STREAM_EOF together with the last chunk of data.
+        let mut chunk = [0u8; 4096];
+        let mut remaining = size as usize;
+        while remaining > 0 {
+            let mut len = chunk.len() as hb::HPDF_UINT32;
+            let result = unsafe { hb::HPDF_ReadFromStream(self.doc, chunk.as_mut_ptr(), &mut len) };
+            if result != 0 && result != hb::HPDF_STREAM_EOF as hb::HPDF_STATUS {
+                return Err(self.error("HPDF_ReadFromStream", result));
+            }
+
+            let len = (len as usize).min(remaining);
+            writer
+                .write_all(&chunk[..len])
+                .map_err(HaruError::from_io_error)?;
+            remaining -= len;
+
+            if len == 0 || result != 0 {
+                break;
+            }
+        }
+
+        // The stream ended before the size libharu reported for it.
+        if remaining > 0 {
+            return Err(HaruError::from(hb::HPDF_STREAM_EOF as u64).with_api("HPDF_ReadFromStream"));
+        }
+
+        writer.flush().map_err(HaruError::from_io_error)?;
+        Ok(self)
+    }
+
     /// Returns the last error code of specified document object.
     ///
-    /// API: HPDF_GetError
+    /// API: HPDF_GetError, HPDF_GetErrorDetail
     ///
     pub fn get_error(&self) -> HaruError {
         let error = unsafe { hb::HPDF_GetError(self.doc) };
-        HaruError::from(error)
+        let detail = unsafe { hb::HPDF_GetErrorDetail(self.doc) };
+        HaruError::new(error, detail)
+    }
+
+    /// reset_error() clears the error state of the document. libharu refuses
+    /// most operations on a document after an error until this is called.
+    /// last_error() returns None afterwards.
+    ///
+    /// API: HPDF_ResetError
+    ///
+    pub fn reset_error(&self) -> &Self {
+        unsafe {
+            hb::HPDF_ResetError(self.doc);
+            if let Ok(mut last_error) = (*self.error_state).last_error.try_borrow_mut() {
+                *last_error = None;
+            }
+        }
+        self
     }
 
     /// Create a new page.
     ///
     /// API: HPDF_AddPage
     ///
-    pub fn add_page(&self) -> Result<PdfPage, HaruError> {
+    pub fn add_page(&self) -> Result<PdfPage<'_>, HaruError> {
         let page: *mut hb::_HPDF_Dict_Rec = unsafe { hb::HPDF_AddPage(self.doc) };
         match page.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfPage { page }),
+            true => Err(self.error("HPDF_AddPage", 0)),
+            false => Ok(PdfPage { page, doc: self }),
+        }
+    }
+
+    /// page_count() returns the number of pages in the document.
+    ///
+    pub fn page_count(&self) -> u32 {
+        unsafe { (*(*self.doc).page_list).count }
+    }
+
+    /// page() returns the page at the given index, counting from 0.
+    ///
+    /// API: HPDF_GetPageByIndex
+    ///
+    pub fn page(&self, index: u32) -> Result<PdfPage<'_>, HaruError> {
+        let page = unsafe { hb::HPDF_GetPageByIndex(self.doc, index) };
+        match page.is_null() {
+            true => Err(self.error("HPDF_GetPageByIndex", 0)),
+            false => Ok(PdfPage { page, doc: self }),
+        }
+    }
+
+    /// current_page() returns the page that was added last, or None if the
+    /// document has no pages yet. Inserted pages don't change the current page.
+    ///
+    /// API: HPDF_GetCurrentPage
+    ///
+    pub fn current_page(&self) -> Option<PdfPage<'_>> {
+        let page = unsafe { hb::HPDF_GetCurrentPage(self.doc) };
+        match page.is_null() {
+            true => None,
+            false => Some(PdfPage { page, doc: self }),
         }
     }
 
+    /// pages() returns an iterator over all pages of the document, in
+    /// document order.
+    ///
+    pub fn pages(&self) -> Pages<'_> {
+        Pages {
+            doc: self,
+            index: 0,
+        }
+    }
+
+    /// add_page_with() creates a new page with a predefined size and
+    /// direction and adds it after the last page of the document.
+    ///
+    /// API: HPDF_AddPage, HPDF_Page_SetSize
+    ///
+    pub fn add_page_with(
+        &self,
+        size: PageSize,
+        direction: PageDirection,
+    ) -> Result<PdfPage<'_>, HaruError> {
+        let page = self.add_page()?;
+        page.set_size(size, direction)?;
+        Ok(page)
+    }
+
     /// insert_page() creates a new page and inserts it just before the specified page.
     ///
     /// API: HPDF_InsertPage
     ///
-    pub fn insert_page(&self, page: PdfPage) -> Result<PdfPage, HaruError> {
+    pub fn insert_page(&self, page: PdfPage<'_>) -> Result<PdfPage<'_>, HaruError> {
+        self.ensure_same(page.doc, "HPDF_InsertPage", hb::HPDF_INVALID_PAGE)?;
+
         // Possible error codes:
         // HPDF_INVALID_DOCUMENT - An invalid document handle was set.
         // HPDF_FAILD_TO_ALLOC_MEM - Memory allocation failed.
         // HPDF_INVALID_PAGE - An invalid page handle was set.
         let page: *mut hb::_HPDF_Dict_Rec = unsafe { hb::HPDF_InsertPage(self.doc, page.page) };
         match page.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfPage { page }),
+            true => Err(self.error("HPDF_InsertPage", 0)),
+            false => Ok(PdfPage { page, doc: self }),
+        }
+    }
+
+    /// create_outline() creates a new top-level outline (bookmark) entry.
+    ///
+    /// The title is interpreted using the encoder named by encoding_name,
+    /// or as PDFDocEncoding when None is passed. To use Unicode titles,
+    /// call use_utf_encodings() and pass Some("UTF-8").
+    ///
+    /// API: HPDF_CreateOutline
+    ///
+    pub fn create_outline(
+        &self,
+        title: &str,
+        encoding_name: Option<&str>,
+    ) -> Result<PdfOutline<'_>, HaruError> {
+        self.create_outline_below(core::ptr::null_mut(), title, encoding_name)
+    }
+
+    /// Creates an outline entry below `parent`, or at the top level when
+    /// `parent` is NULL.
+    pub(crate) fn create_outline_below(
+        &self,
+        parent: hb::HPDF_Outline,
+        title: &str,
+        encoding_name: Option<&str>,
+    ) -> Result<PdfOutline<'_>, HaruError> {
+        let encoder = match encoding_name {
+            Some(encoding_name) => {
+                let encoding_name = std::ffi::CString::new(encoding_name).unwrap();
+                let encoder = unsafe { hb::HPDF_GetEncoder(self.doc, encoding_name.as_ptr()) };
+                if encoder.is_null() {
+                    return Err(self.error("HPDF_GetEncoder", 0));
+                }
+                encoder
+            }
+            None => core::ptr::null_mut(),
+        };
+
+        let title = std::ffi::CString::new(title).unwrap();
+        let outline = unsafe { hb::HPDF_CreateOutline(self.doc, parent, title.as_ptr(), encoder) };
+        match outline.is_null() {
+            true => Err(self.error("HPDF_CreateOutline", 0)),
+            false => Ok(PdfOutline { outline, doc: self }),
+        }
+    }
+
+    /// create_outline_tree() builds a nested outline from a flat list of
+    /// `(level, title, page)` entries, as produced by a table of contents.
+    /// Level 0 entries are created at the top level, and each entry may be
+    /// at most one level deeper than the one before it.
+    ///
+    /// Returns the created outlines in the order of the entries.
+    ///
+    /// API: HPDF_CreateOutline, HPDF_Outline_SetDestination
+    ///
+    pub fn create_outline_tree(
+        &self,
+        entries: &[(usize, &str, PdfPage<'_>)],
+        encoding_name: Option<&str>,
+    ) -> Result<Vec<PdfOutline<'_>>, HaruError> {
+        let mut parents: Vec<hb::HPDF_Outline> = Vec::new();
+        let mut outlines = Vec::with_capacity(entries.len());
+
+        for (level, title, page) in entries {
+            if *level > parents.len() {
+                return Err(HaruError::from(hb::HPDF_INVALID_PARAMETER as u64)
+                    .with_api("HPDF_CreateOutline"));
+            }
+            parents.truncate(*level);
+
+            let parent = parents.last().copied().unwrap_or(core::ptr::null_mut());
+            self.ensure_same(page.doc, "HPDF_CreateOutline", hb::HPDF_INVALID_PAGE)?;
+            let outline = self.create_outline_below(parent, title, encoding_name)?;
+            outline.set_destination(&page.create_destination(Destination::Fit)?)?;
+
+            parents.push(outline.outline);
+            outlines.push(outline);
+        }
+
+        Ok(outlines)
+    }
+
+    /// add_page_label() sets the page labels viewers display, starting at the
+    /// zero-based page index start_page and continuing up to the next label range.
+    ///
+    /// For example, `add_page_label(0, PageNumStyle::LowerRoman, 1, None)` labels
+    /// the preface "i, ii, iii", and `add_page_label(40, PageNumStyle::Decimal, 1,
+    /// Some("A-"))` labels an appendix "A-1, A-2, A-3".
+    ///
+    /// API: HPDF_AddPageLabel
+    ///
+    pub fn add_page_label(
+        &self,
+        start_page: u32,
+        style: PageNumStyle,
+        first_number: u32,
+        prefix: Option<&str>,
+    ) -> Result<&Self, HaruError> {
+        let prefix = prefix.map(|prefix| std::ffi::CString::new(prefix).unwrap());
+        let result = unsafe {
+            hb::HPDF_AddPageLabel(
+                self.doc,
+                start_page,
+                style.to_hpdf_page_num_style(),
+                first_number,
+                prefix
+                    .as_ref()
+                    .map_or(core::ptr::null(), |prefix| prefix.as_ptr()),
+            )
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_AddPageLabel", result)),
+        }
+    }
+
+    /// set_open_action() sets the destination the document opens at, e.g.
+    /// a specific page and zoom level.
+    ///
+    /// API: HPDF_SetOpenAction
+    ///
+    pub fn set_open_action(&self, dst: &PdfDestination<'_>) -> Result<&Self, HaruError> {
+        self.ensure_same(dst.doc, "HPDF_SetOpenAction", hb::HPDF_INVALID_DESTINATION)?;
+        let result = unsafe { hb::HPDF_SetOpenAction(self.doc, dst.dst) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_SetOpenAction", result)),
         }
     }
 
     /// Set the title of the document.
     ///
     pub fn set_title(&self, title: &str) -> Result<&Self, HaruError> {
-        let title = std::ffi::CString::new(title).unwrap();
+        self.set_info(InfoAttr::Title, title)
+    }
+
+    /// set_info() sets the text of an info dictionary attribute, e.g. the author.
+    /// Use set_info_date() for CreationDate and ModDate.
+    ///
+    /// API: HPDF_SetInfoAttr
+    ///
+    pub fn set_info(&self, attr: InfoAttr, value: &str) -> Result<&Self, HaruError> {
+        self.ensure_info_writable("HPDF_SetInfoAttr")?;
+        let value = std::ffi::CString::new(value).unwrap();
+        let result =
+            unsafe { hb::HPDF_SetInfoAttr(self.doc, attr.to_hpdf_info_attr(), value.as_ptr()) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_SetInfoAttr", result)),
+        }
+    }
+
+    /// get_info() returns the value of an info dictionary attribute, or None
+    /// if the attribute is not set. Dates are returned in PDF format
+    /// ("D:YYYYMMDDHHmmSSOHH'mm'").
+    ///
+    /// API: HPDF_GetInfoAttr
+    ///
+    pub fn get_info(&self, attr: InfoAttr) -> Option<String> {
+        let value = unsafe { hb::HPDF_GetInfoAttr(self.doc, attr.to_hpdf_info_attr()) };
+        if value.is_null() {
+            return None;
+        }
+        let value = unsafe { std::ffi::CStr::from_ptr(value) };
+        Some(value.to_string_lossy().into_owned())
+    }
+
+    /// set_info_date() sets the CreationDate or ModDate attribute of the document.
+    ///
+    /// API: HPDF_SetInfoDateAttr
+    ///
+    pub fn set_info_date(&self, attr: InfoAttr, date: PdfDate) -> Result<&Self, HaruError> {
+        self.ensure_info_writable("HPDF_SetInfoDateAttr")?;
+        if !date.is_valid() {
+            return Err(
+                HaruError::from(hb::HPDF_INVALID_DATE_TIME as u64).with_api("HPDF_SetInfoDateAttr")
+            );
+        }
+
         let result = unsafe {
-            hb::HPDF_SetInfoAttr(self.doc, hb::_HPDF_InfoType_HPDF_INFO_TITLE, title.as_ptr())
+            hb::HPDF_SetInfoDateAttr(self.doc, attr.to_hpdf_info_attr(), date.to_hpdf_date())
         };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetInfoDateAttr", result)),
         }
     }
 
     /// HPDF_SetPassword() sets a password for the document. If the password is set, document contents are encrypted.
     ///
     pub fn set_password(&self, owner_passwd: &str, user_passwd: &str) -> Result<&Self, HaruError> {
+        self.ensure_not_pdfa("HPDF_SetPassword")?;
         let owner_passwd = std::ffi::CString::new(owner_passwd).unwrap();
         let user_passwd = std::ffi::CString::new(user_passwd).unwrap();
         let result =
             unsafe { hb::HPDF_SetPassword(self.doc, owner_passwd.as_ptr(), user_passwd.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetPassword", result)),
+        }
+    }
+
+    /// set_permission() restricts what a user may do with the document.
+    /// A password must be set with set_password() before calling this.
+    ///
+    /// API: HPDF_SetPermission
+    ///
+    pub fn set_permission(&self, permission: Permissions) -> Result<&Self, HaruError> {
+        self.ensure_not_pdfa("HPDF_SetPermission")?;
+        // The reserved bits of /P must be 1, but libharu stores the value as is.
+        let permission = permission.to_hpdf_permission() | hb::HPDF_PERMISSION_PAD;
+        let result = unsafe { hb::HPDF_SetPermission(self.doc, permission) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_SetPermission", result)),
+        }
+    }
+
+    /// set_encryption_mode() sets the encryption revision and key length.
+    /// A password must be set with set_password() before calling this.
+    ///
+    /// API: HPDF_SetEncryptionMode
+    ///
+    pub fn set_encryption_mode(&self, mode: EncryptionMode) -> Result<&Self, HaruError> {
+        self.ensure_not_pdfa("HPDF_SetEncryptionMode")?;
+        let (mode, key_len) = mode.to_hpdf_encrypt_mode();
+        let result = unsafe { hb::HPDF_SetEncryptionMode(self.doc, mode, key_len) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_SetEncryptionMode", result)),
         }
     }
 
@@ -218,7 +1334,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_SetCompressionMode(self.doc, mode.to_hpdf_compression()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetCompressionMode", result)),
         }
     }
 
@@ -228,14 +1344,21 @@ impl PdfDocument {
     ///
     pub fn set_font_and_size(
         &self,
-        page: &PdfPage,
-        font: &PdfFont,
+        page: &PdfPage<'_>,
+        font: &PdfFont<'_>,
         size: f32,
     ) -> Result<&Self, HaruError> {
+        self.ensure_same(page.doc, "HPDF_Page_SetFontAndSize", hb::HPDF_INVALID_PAGE)?;
+        self.ensure_same(
+            font.doc,
+            "HPDF_Page_SetFontAndSize",
+            hb::HPDF_PAGE_INVALID_FONT,
+        )?;
+        self.ensure_pdfa_font(font, "HPDF_Page_SetFontAndSize")?;
         let result = unsafe { hb::HPDF_Page_SetFontAndSize(page.page, font.font_ref, size) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetFontAndSize", result)),
         }
     }
 
@@ -248,13 +1371,16 @@ impl PdfDocument {
         filename: &str,
         embedding: bool,
     ) -> Result<String, HaruError> {
+        if !embedding {
+            self.ensure_not_pdfa("HPDF_LoadTTFontFromFile")?;
+        }
         let filename = std::ffi::CString::new(filename).unwrap();
         let embedding = if embedding { 1 } else { 0 };
         let fontname =
             unsafe { hb::HPDF_LoadTTFontFromFile(self.doc, filename.as_ptr(), embedding) };
 
         if fontname.is_null() {
-            return Err(HaruError::from(0));
+            return Err(self.error("HPDF_LoadTTFontFromFile", 0));
         }
 
         let fontname = unsafe { std::ffi::CStr::from_ptr(fontname) };
@@ -285,18 +1411,24 @@ impl PdfDocument {
         filename: &str,
         index: u32,
         embedding: bool,
-    ) -> Result<PdfFont, HaruError> {
+    ) -> Result<PdfFont<'_>, HaruError> {
+        if !embedding {
+            self.ensure_not_pdfa("HPDF_LoadTTFontFromFile2")?;
+        }
         let filename = std::ffi::CString::new(filename).unwrap();
         let embedding = if embedding { 1 } else { 0 };
         let fontname =
             unsafe { hb::HPDF_LoadTTFontFromFile2(self.doc, filename.as_ptr(), index, embedding) };
         if fontname.is_null() {
-            return Err(HaruError::from(0));
+            return Err(self.error("HPDF_LoadTTFontFromFile2", 0));
         }
         let font = unsafe { hb::HPDF_GetFont(self.doc, fontname, core::ptr::null_mut()) };
         match font.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfFont { font_ref: font }),
+            true => Err(self.error("HPDF_GetFont", 0)),
+            false => Ok(PdfFont {
+                font_ref: font,
+                doc: self,
+            }),
         }
     }
 
@@ -308,19 +1440,22 @@ impl PdfDocument {
         &self,
         afm_filename: &str,
         pfm_filename: &str,
-    ) -> Result<PdfFont, HaruError> {
+    ) -> Result<PdfFont<'_>, HaruError> {
         let afm_filename = std::ffi::CString::new(afm_filename).unwrap();
         let pfm_filename = std::ffi::CString::new(pfm_filename).unwrap();
         let fontname = unsafe {
             hb::HPDF_LoadType1FontFromFile(self.doc, afm_filename.as_ptr(), pfm_filename.as_ptr())
         };
         if fontname.is_null() {
-            return Err(HaruError::from(0));
+            return Err(self.error("HPDF_LoadType1FontFromFile", 0));
         }
         let font = unsafe { hb::HPDF_GetFont(self.doc, fontname, core::ptr::null_mut()) };
         match font.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfFont { font_ref: font }),
+            true => Err(self.error("HPDF_GetFont", 0)),
+            false => Ok(PdfFont {
+                font_ref: font,
+                doc: self,
+            }),
         }
     }
 
@@ -332,7 +1467,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_UseCNSFonts(self.doc) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_UseCNSFonts", result)),
         }
     }
 
@@ -344,7 +1479,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_UseCNTFonts(self.doc) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_UseCNTFonts", result)),
         }
     }
 
@@ -356,7 +1491,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_UseJPFonts(self.doc) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_UseJPFonts", result)),
         }
     }
 
@@ -368,7 +1503,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_UseUTFEncodings(self.doc) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_UseUTFEncodings", result)),
         }
     }
 
@@ -381,7 +1516,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_SetCurrentEncoder(self.doc, encoding_name.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_SetCurrentEncoder", result)),
         }
     }
 
@@ -393,7 +1528,7 @@ impl PdfDocument {
         let result = unsafe { hb::HPDF_UseKRFonts(self.doc) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_UseKRFonts", result)),
         }
     }
 
@@ -401,12 +1536,15 @@ impl PdfDocument {
     ///
     /// API: HPDF_LoadPngImageFromFile
     ///
-    pub fn load_png_image_from_file(&self, filename: &str) -> Result<PdfImage, HaruError> {
+    pub fn load_png_image_from_file(&self, filename: &str) -> Result<PdfImage<'_>, HaruError> {
         let filename = std::ffi::CString::new(filename).unwrap();
         let image = unsafe { hb::HPDF_LoadPngImageFromFile(self.doc, filename.as_ptr()) };
         match image.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfImage { image_ref: image }),
+            true => Err(self.error("HPDF_LoadPngImageFromFile", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
         }
     }
 
@@ -418,12 +1556,379 @@ impl PdfDocument {
     ///
     /// API: HPDF_LoadPngImageFromFile2
     ///
-    pub fn load_png_image_from_file2(&self, filename: &str) -> Result<PdfImage, HaruError> {
+    pub fn load_png_image_from_file2(&self, filename: &str) -> Result<PdfImage<'_>, HaruError> {
         let filename = std::ffi::CString::new(filename).unwrap();
         let image = unsafe { hb::HPDF_LoadPngImageFromFile2(self.doc, filename.as_ptr()) };
         match image.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfImage { image_ref: image }),
+            true => Err(self.error("HPDF_LoadPngImageFromFile2", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
+        }
+    }
+
+    /// load_raw_image() loads an image from uncompressed pixel data. Each
+    /// row starts on a byte boundary and holds `width` pixels of the color
+    /// space's components with `bits_per_component` (1, 2, 4 or 8) bits
+    /// each. The length of `data` has to match exactly.
+    ///
+    /// Below 8 bits per component, libharu doesn't pad the rows, so the
+    /// rows have to fill whole bytes. 1 bit gray images are the exception.
+    ///
+    /// API: HPDF_LoadRawImageFromMem
+    ///
+    pub fn load_raw_image(
+        &self,
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+        bits_per_component: u32,
+        data: &[u8],
+    ) -> Result<PdfImage<'_>, HaruError> {
+        let invalid_image =
+            HaruError::from(hb::HPDF_INVALID_IMAGE as u64).with_api("HPDF_LoadRawImageFromMem");
+        if !matches!(bits_per_component, 1 | 2 | 4 | 8) || width == 0 || height == 0 {
+            return Err(invalid_image);
+        }
+
+        let gray_bitmap = color_space == ColorSpace::DeviceGray && bits_per_component == 1;
+        if bits_per_component < 8
+            && !gray_bitmap
+            && (width as u64 * bits_per_component as u64) & 7 != 0
+        {
+            return Err(invalid_image);
+        }
+
+        let row_bits =
+            width as usize * color_space.num_components() as usize * bits_per_component as usize;
+        if data.len() != row_bits.div_ceil(8) * height as usize {
+            return Err(invalid_image);
+        }
+
+        // libharu encodes bitmaps with CCITT G4 but only declares the filter
+        // if image compression is enabled.
+        if gray_bitmap {
+            return self.load_raw_1bit_image(width, height, width.div_ceil(8), false, data);
+        }
+
+        let image = unsafe {
+            hb::HPDF_LoadRawImageFromMem(
+                self.doc,
+                data.as_ptr(),
+                width,
+                height,
+                color_space.to_hpdf_color_space(),
+                bits_per_component,
+            )
+        };
+        match image.is_null() {
+            true => Err(self.error("HPDF_LoadRawImageFromMem", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
+        }
+    }
+
+    /// load_raw_1bit_image() loads a bilevel image from uncompressed rows of
+    /// `line_width` bytes each. `line_width` is at least `(width + 7) / 8`;
+    /// larger values skip the padding of rows aligned to more than a byte.
+    /// The image is stored CCITT G4 compressed.
+    ///
+    /// With `black_is_1` set bits are painted black, as in fax and TIFF
+    /// bilevel data. Otherwise set bits are white, like in 1 bit gray
+    /// images.
+    ///
+    /// API: HPDF_Image_LoadRaw1BitImageFromMem
+    ///
+    pub fn load_raw_1bit_image(
+        &self,
+        width: u32,
+        height: u32,
+        line_width: u32,
+        black_is_1: bool,
+        data: &[u8],
+    ) -> Result<PdfImage<'_>, HaruError> {
+        if width == 0
+            || height == 0
+            || line_width < width.div_ceil(8)
+            || data.len() as u64 != line_width as u64 * height as u64
+        {
+            return Err(HaruError::from(hb::HPDF_INVALID_IMAGE as u64)
+                .with_api("HPDF_Image_LoadRaw1BitImageFromMem"));
+        }
+
+        let image = unsafe {
+            hb::HPDF_Image_LoadRaw1BitImageFromMem(
+                self.doc,
+                data.as_ptr(),
+                width,
+                height,
+                line_width,
+                (!black_is_1) as hb::HPDF_BOOL,
+                hb::HPDF_TRUE as hb::HPDF_BOOL,
+            )
+        };
+        if image.is_null() {
+            return Err(self.error("HPDF_Image_LoadRaw1BitImageFromMem", 0));
+        }
+
+        // The encoder treats set bits as black, so the decoder has to invert
+        // them unless they are meant to be black.
+        unsafe {
+            self.set_ccitt_decode(
+                image,
+                width,
+                height,
+                CcittEncoding::Group4,
+                !black_is_1,
+                false,
+            )?
+        };
+        Ok(PdfImage {
+            image_ref: image,
+            doc: self,
+        })
+    }
+
+    /// load_ccitt_image() loads a bilevel image from CCITT fax encoded data,
+    /// e.g. the strips of a G3 or G4 compressed TIFF. The data is embedded as
+    /// is.
+    ///
+    /// With `black_is_1` 1 bits of the decoded data are black, otherwise 0
+    /// bits are. `byte_aligned` tells that each encoded line starts on a byte
+    /// boundary.
+    ///
+    /// API: HPDF_DictStream_New
+    ///
+    pub fn load_ccitt_image(
+        &self,
+        width: u32,
+        height: u32,
+        encoding: CcittEncoding,
+        black_is_1: bool,
+        byte_aligned: bool,
+        data: &[u8],
+    ) -> Result<PdfImage<'_>, HaruError> {
+        let invalid_image =
+            HaruError::from(hb::HPDF_INVALID_IMAGE as u64).with_api("HPDF_DictStream_New");
+        if width == 0 || height == 0 || data.is_empty() || encoding == CcittEncoding::Group3TwoD(0)
+        {
+            return Err(invalid_image);
+        }
+        let (Ok(w), Ok(h), Ok(len)) = (
+            i32::try_from(width),
+            i32::try_from(height),
+            u32::try_from(data.len()),
+        ) else {
+            return Err(invalid_image);
+        };
+
+        let key = |key: &str| std::ffi::CString::new(key).unwrap();
+        unsafe {
+            // The objects are created directly, so check that the document
+            // is valid and has no error pending first.
+            if hb::HPDF_HasDoc(self.doc) == hb::HPDF_FALSE as hb::HPDF_BOOL {
+                return Err(self.error("HPDF_HasDoc", 0));
+            }
+            let image = hb::HPDF_DictStream_New((*self.doc).mmgr, (*self.doc).xref);
+            if image.is_null() {
+                return Err(self.error("HPDF_DictStream_New", 0));
+            }
+            (*image).header.obj_class |= hb::HPDF_OSUBCLASS_XOBJECT as hb::HPDF_UINT16;
+
+            for (name, value) in [
+                ("Type", "XObject"),
+                ("Subtype", "Image"),
+                ("ColorSpace", "DeviceGray"),
+            ] {
+                self.check(
+                    "HPDF_Dict_AddName",
+                    hb::HPDF_Dict_AddName(image, key(name).as_ptr(), key(value).as_ptr()),
+                )?;
+            }
+            for (name, value) in [("Width", w), ("Height", h), ("BitsPerComponent", 1)] {
+                self.check(
+                    "HPDF_Dict_AddNumber",
+                    hb::HPDF_Dict_AddNumber(image, key(name).as_ptr(), value),
+                )?;
+            }
+            self.check(
+                "HPDF_Stream_Write",
+                hb::HPDF_Stream_Write((*image).stream, data.as_ptr(), len),
+            )?;
+
+            self.set_ccitt_decode(image, width, height, encoding, black_is_1, byte_aligned)?;
+            Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            })
+        }
+    }
+
+    /// Declares the CCITTFaxDecode filter and its parameters on an image.
+    /// libharu's own filter parameters are replaced, as they are added to
+    /// the image again each time the document is saved.
+    unsafe fn set_ccitt_decode(
+        &self,
+        image: hb::HPDF_Image,
+        width: u32,
+        height: u32,
+        encoding: CcittEncoding,
+        black_is_1: bool,
+        byte_aligned: bool,
+    ) -> Result<(), HaruError> {
+        let key = |key: &str| std::ffi::CString::new(key).unwrap();
+        let params = hb::HPDF_Dict_New((*self.doc).mmgr);
+        if params.is_null() {
+            return Err(self.error("HPDF_Dict_New", 0));
+        }
+
+        for (name, value) in [
+            ("K", encoding.k()),
+            ("Columns", width as i32),
+            ("Rows", height as i32),
+        ] {
+            self.check(
+                "HPDF_Dict_AddNumber",
+                hb::HPDF_Dict_AddNumber(params, key(name).as_ptr(), value),
+            )?;
+        }
+        for (name, set) in [("BlackIs1", black_is_1), ("EncodedByteAlign", byte_aligned)] {
+            if set {
+                self.check(
+                    "HPDF_Dict_AddBoolean",
+                    hb::HPDF_Dict_AddBoolean(
+                        params,
+                        key(name).as_ptr(),
+                        hb::HPDF_TRUE as hb::HPDF_BOOL,
+                    ),
+                )?;
+            }
+        }
+        self.check(
+            "HPDF_Dict_Add",
+            hb::HPDF_Dict_Add(image, key("DecodeParms").as_ptr(), params as *mut _),
+        )?;
+
+        (*image).filter = hb::HPDF_STREAM_FILTER_CCITT_DECODE;
+        (*image).filterParams = std::ptr::null_mut();
+        Ok(())
+    }
+
+    /// load_dynamic_image() loads an image decoded by the `image` crate. The
+    /// pixels are converted to 8 bit gray or RGB; an alpha channel becomes
+    /// the soft mask of the image, so images with alpha are refused in PDF/A
+    /// documents.
+    ///
+    /// Requires the `image` feature, which enables the PNG and JPEG decoders
+    /// of the `image` crate. Enable its other format features to decode more
+    /// formats. The `image` crate needs Rust 1.88 or newer.
+    ///
+    /// API: HPDF_LoadRawImageFromMem, HPDF_Image_AddSMask
+    ///
+    #[cfg(feature = "image")]
+    pub fn load_dynamic_image(
+        &self,
+        image: &image::DynamicImage,
+    ) -> Result<PdfImage<'_>, HaruError> {
+        let (width, height) = (image.width(), image.height());
+        let color = image.color();
+        if color.has_alpha() {
+            self.ensure_not_pdfa("HPDF_Image_AddSMask")?;
+        }
+
+        let (pdf_image, alpha) = match (color.has_color(), color.has_alpha()) {
+            (false, false) => {
+                let gray = image.to_luma8();
+                let pdf_image =
+                    self.load_raw_image(width, height, ColorSpace::DeviceGray, 8, gray.as_raw())?;
+                (pdf_image, None)
+            }
+            (true, false) => {
+                let rgb = image.to_rgb8();
+                let pdf_image =
+                    self.load_raw_image(width, height, ColorSpace::DeviceRGB, 8, rgb.as_raw())?;
+                (pdf_image, None)
+            }
+            (false, true) => {
+                let pixels = image.to_luma_alpha8();
+                let gray: Vec<u8> = pixels.pixels().map(|p| p.0[0]).collect();
+                let alpha: Vec<u8> = pixels.pixels().map(|p| p.0[1]).collect();
+                let pdf_image =
+                    self.load_raw_image(width, height, ColorSpace::DeviceGray, 8, &gray)?;
+                (pdf_image, Some(alpha))
+            }
+            (true, true) => {
+                let pixels = image.to_rgba8();
+                let rgb: Vec<u8> = pixels
+                    .pixels()
+                    .flat_map(|p| [p.0[0], p.0[1], p.0[2]])
+                    .collect();
+                let alpha: Vec<u8> = pixels.pixels().map(|p| p.0[3]).collect();
+                let pdf_image =
+                    self.load_raw_image(width, height, ColorSpace::DeviceRGB, 8, &rgb)?;
+                (pdf_image, Some(alpha))
+            }
+        };
+
+        if let Some(alpha) = alpha {
+            let smask = self.load_raw_image(width, height, ColorSpace::DeviceGray, 8, &alpha)?;
+            pdf_image.add_smask(&smask)?;
+        }
+
+        Ok(pdf_image)
+    }
+
+    /// load_raw_image_from_file() loads an image from a file of uncompressed
+    /// pixel data with 8 bits per component. The file size has to match the
+    /// size of the image.
+    ///
+    /// API: HPDF_LoadRawImageFromFile
+    ///
+    pub fn load_raw_image_from_file(
+        &self,
+        filename: &str,
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+    ) -> Result<PdfImage<'_>, HaruError> {
+        let filename = std::ffi::CString::new(filename).unwrap();
+        let image = unsafe {
+            hb::HPDF_LoadRawImageFromFile(
+                self.doc,
+                filename.as_ptr(),
+                width,
+                height,
+                color_space.to_hpdf_color_space(),
+            )
+        };
+        match image.is_null() {
+            true => Err(self.error("HPDF_LoadRawImageFromFile", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
+        }
+    }
+
+    /// load_png_image_from_bytes() loads a PNG image from memory.
+    ///
+    /// API: HPDF_LoadPngImageFromMem
+    ///
+    pub fn load_png_image_from_bytes(&self, data: &[u8]) -> Result<PdfImage<'_>, HaruError> {
+        let Ok(len) = u32::try_from(data.len()) else {
+            return Err(HaruError::from(hb::HPDF_INVALID_PARAMETER as u64)
+                .with_api("HPDF_LoadPngImageFromMem"));
+        };
+        let image = unsafe { hb::HPDF_LoadPngImageFromMem(self.doc, data.as_ptr(), len) };
+        match image.is_null() {
+            true => Err(self.error("HPDF_LoadPngImageFromMem", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
         }
     }
 
@@ -431,18 +1936,643 @@ impl PdfDocument {
     ///
     /// API: HPDF_LoadJpegImageFromFile
     ///
-    pub fn load_jpeg_image_from_file(&self, filename: &str) -> Result<PdfImage, HaruError> {
+    pub fn load_jpeg_image_from_file(&self, filename: &str) -> Result<PdfImage<'_>, HaruError> {
         let filename = std::ffi::CString::new(filename).unwrap();
         let image = unsafe { hb::HPDF_LoadJpegImageFromFile(self.doc, filename.as_ptr()) };
         match image.is_null() {
-            true => Err(HaruError::from(0)),
-            false => Ok(PdfImage { image_ref: image }),
+            true => Err(self.error("HPDF_LoadJpegImageFromFile", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
+        }
+    }
+
+    /// load_jpeg_image_from_bytes() loads a JPEG image from memory.
+    ///
+    /// API: HPDF_LoadJpegImageFromMem
+    ///
+    pub fn load_jpeg_image_from_bytes(&self, data: &[u8]) -> Result<PdfImage<'_>, HaruError> {
+        let Ok(len) = u32::try_from(data.len()) else {
+            return Err(HaruError::from(hb::HPDF_INVALID_PARAMETER as u64)
+                .with_api("HPDF_LoadJpegImageFromMem"));
+        };
+        let image = unsafe { hb::HPDF_LoadJpegImageFromMem(self.doc, data.as_ptr(), len) };
+        match image.is_null() {
+            true => Err(self.error("HPDF_LoadJpegImageFromMem", 0)),
+            false => Ok(PdfImage {
+                image_ref: image,
+                doc: self,
+            }),
         }
     }
 }
 
 impl Drop for PdfDocument {
     fn drop(&mut self) {
-        unsafe { hb::HPDF_Free(self.doc) };
+        unsafe {
+            hb::HPDF_Free(self.doc);
+            drop(Box::from_raw(self.error_state));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haru_types::{BlendMode, CcittEncoding, HaruErrorKind, Rect};
+
+    #[test]
+    fn test_save_to_bytes() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+
+        let bytes = doc.save_to_bytes().unwrap();
+        assert!(bytes.starts_with(b"%PDF-"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+
+        struct FailingWriter;
+        impl Write for FailingWriter {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let err = doc.save_to_writer(&mut FailingWriter).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::FileIoError);
+        assert_eq!(err.io_error().unwrap().to_string(), "disk full");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_info_attributes() {
+        let doc = PdfDocument::new();
+        assert_eq!(doc.get_info(InfoAttr::Author), None);
+
+        doc.set_info(InfoAttr::Author, "Jane Doe").unwrap();
+        doc.set_info_date(
+            InfoAttr::CreationDate,
+            PdfDate::new(2024, 3, 20, 14, 30, 0).with_utc_offset(-(5 * 60 + 30)),
+        )
+        .unwrap();
+
+        assert_eq!(doc.get_info(InfoAttr::Author).as_deref(), Some("Jane Doe"));
+        assert_eq!(
+            doc.get_info(InfoAttr::CreationDate).as_deref(),
+            Some("D:20240320143000-05'30'")
+        );
+
+        doc.set_info_date(
+            InfoAttr::ModDate,
+            PdfDate::new(2024, 3, 20, 14, 30, 0).with_utc_offset(-(4 * 60 + 30)),
+        )
+        .unwrap();
+        assert_eq!(
+            doc.get_info(InfoAttr::ModDate).as_deref(),
+            Some("D:20240320143000-04'30'")
+        );
+
+        let err = doc
+            .set_info_date(
+                InfoAttr::ModDate,
+                PdfDate::new(2024, 3, 20, 14, 30, 0).with_utc_offset(24 * 60),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidDateTime);
+        assert!(doc.save_to_bytes().is_ok());
+    }
+
+    #[test]
+    fn test_encryption_settings() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+
+        doc.set_password("owner", "").unwrap();
+        let mut permissions = Permissions::empty();
+        assert!(!permissions.contains(Permissions::PRINT));
+        permissions |= Permissions::PRINT;
+        assert!(permissions.contains(Permissions::PRINT));
+        doc.set_permission(permissions).unwrap();
+        doc.set_encryption_mode(EncryptionMode::R3 { key_len: 16 })
+            .unwrap();
+
+        let bytes = doc.save_to_bytes().unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/Encrypt "));
+        assert!(pdf.contains("/Filter /Standard\n"));
+        assert!(pdf.contains("/R 3\n"));
+        assert!(pdf.contains("/Length 128\n"));
+        // Print only, with the reserved bits set as the spec requires.
+        let p = (Permissions::PRINT.to_hpdf_permission() | hb::HPDF_PERMISSION_PAD) as i32;
+        assert_eq!(p, -60);
+        assert!(pdf.contains(&format!("/P {}\n", p)));
+    }
+
+    #[test]
+    fn test_error_details() {
+        let reported = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = reported.clone();
+        let doc = PdfDocument::with_error_handler(move |err| sink.borrow_mut().push(err.clone()));
+
+        let err = doc.get_font("NoSuchFont", None).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidFontName);
+        assert_eq!(err.api(), Some("HPDF_GetFont"));
+        assert!(err
+            .to_string()
+            .starts_with("HPDF_GetFont failed: InvalidFontName"));
+
+        assert_eq!(reported.borrow().len(), 1);
+        let last = doc.last_error().unwrap();
+        assert_eq!(last.kind(), err.kind());
+
+        // The returned error is a copy, later errors don't touch it.
+        doc.reset_error();
+        let err = doc
+            .get_font("Helvetica", Some("NoSuchEncoding"))
+            .unwrap_err();
+        assert_ne!(err.kind(), HaruErrorKind::InvalidFontName);
+        assert_eq!(last.kind(), HaruErrorKind::InvalidFontName);
+        assert_eq!(doc.last_error().map(|e| e.kind()), Some(err.kind()));
+        assert_eq!(reported.borrow().len(), 2);
+
+        doc.reset_error();
+        assert!(doc.last_error().is_none());
+        assert!(doc.add_page().is_ok());
+    }
+
+    #[test]
+    fn test_handles_of_other_documents_are_rejected() {
+        let doc = PdfDocument::new();
+        let other = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        let font = other.get_font("Helvetica", None).unwrap();
+
+        let err = page.set_font_and_size(font, 12.0).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageInvalidFont);
+        assert!(other.insert_page(page).is_err());
+        assert!(doc.insert_page(page).is_ok());
+    }
+
+    #[test]
+    fn test_outline_tree() {
+        let doc = PdfDocument::new();
+        doc.use_utf_encodings().unwrap();
+        let first = doc.add_page().unwrap();
+        let second = doc.add_page().unwrap();
+
+        let outlines = doc
+            .create_outline_tree(
+                &[
+                    (0, "Einführung", first),
+                    (1, "Überblick", first),
+                    (0, "Anhang", second),
+                ],
+                Some("UTF-8"),
+            )
+            .unwrap();
+        assert_eq!(outlines.len(), 3);
+        outlines[0].set_opened(true).unwrap();
+
+        let err = doc
+            .create_outline_tree(&[(1, "Too deep", first)], None)
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidParameter);
+
+        assert!(doc.save_to_bytes().is_ok());
+    }
+
+    #[test]
+    fn test_viewer_preferences() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+        assert_eq!(doc.get_viewer_preference(), ViewerPreferences::default());
+
+        let prefs = ViewerPreferences::HIDE_TOOLBAR
+            | ViewerPreferences::HIDE_MENUBAR
+            | ViewerPreferences::PRINT_SCALING_NONE;
+        doc.set_viewer_preference(prefs).unwrap();
+
+        let stored = doc.get_viewer_preference();
+        assert_eq!(stored, prefs);
+        assert!(stored.contains(ViewerPreferences::HIDE_MENUBAR));
+        assert!(!stored.contains(ViewerPreferences::FIT_WINDOW));
+    }
+
+    #[test]
+    fn test_page_lookup() {
+        let doc = PdfDocument::new();
+        assert_eq!(doc.page_count(), 0);
+        assert!(doc.current_page().is_none());
+        assert_eq!(doc.pages().count(), 0);
+
+        let first = doc.add_page().unwrap();
+        let last = doc.add_page().unwrap();
+        let second = doc.insert_page(last).unwrap();
+        assert_eq!(doc.page_count(), 3);
+        assert_eq!(doc.current_page().unwrap().page, last.page);
+
+        assert_eq!(doc.page(0).unwrap().page, first.page);
+        assert_eq!(doc.page(1).unwrap().page, second.page);
+        assert_eq!(doc.page(2).unwrap().page, last.page);
+
+        let err = doc.page(3).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidPageIndex);
+        assert_eq!(err.api(), Some("HPDF_GetPageByIndex"));
+        doc.reset_error();
+
+        let pages: Vec<_> = doc.pages().map(|page| page.page).collect();
+        assert_eq!(pages, vec![first.page, second.page, last.page]);
+    }
+
+    #[test]
+    fn test_attachments() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+
+        doc.attach_bytes("invoice.xml", "text/xml", b"<Invoice/>")
+            .unwrap();
+        doc.attach_bytes("appendix.txt", "text/plain", b"appendix")
+            .unwrap();
+        doc.attach_file("Cargo.toml").unwrap();
+
+        let err = doc.attach_file("does-not-exist.xml").unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::FileOpenError);
+        doc.reset_error();
+
+        // File names must be unique.
+        let err = doc
+            .attach_bytes("invoice.xml", "text/xml", b"")
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::DuplicateRegistration);
+        assert_eq!(err.api(), Some("HPDF_AttachFile"));
+        let err = doc.attach_file("Cargo.toml").unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::DuplicateRegistration);
+
+        doc.attach_bytes("Übersicht.txt", "text/plain", b"overview")
+            .unwrap();
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/Type /EmbeddedFile"));
+        assert!(pdf.contains("/Subtype /text#2Fxml"));
+        assert!(pdf.contains("/UF <FEFF0069006E0076006F006900630065002E0078006D006C>"));
+        assert!(pdf.contains("/UF <FEFF00DC00620065007200730069006300680074002E007400780074>"));
+        assert_eq!(pdf.matches("/Type /Filespec").count(), 4);
+
+        // The name tree keeps the file names sorted.
+        let names = &pdf[pdf.find("/Names [").unwrap()..];
+        let names = &names[..names.find(']').unwrap()];
+        let manifest = names.find("(Cargo.toml)").unwrap();
+        let appendix = names.find("(appendix.txt)").unwrap();
+        let invoice = names.find("(invoice.xml)").unwrap();
+        assert!(manifest < appendix && appendix < invoice);
+    }
+
+    #[test]
+    fn test_pdfa() {
+        let icc_path = std::env::temp_dir().join("libharu_ng_test_pdfa.icc");
+        std::fs::write(&icc_path, [0u8; 128]).unwrap();
+
+        let doc = PdfDocument::new();
+        let profile = doc
+            .load_icc_profile_from_file(icc_path.to_str().unwrap(), 3)
+            .unwrap();
+        std::fs::remove_file(&icc_path).unwrap();
+        doc.enable_pdfa(PdfAConformance::PdfA1b, "sRGB IEC61966-2.1", &profile)
+            .unwrap();
+        assert_eq!(doc.get_pdfa(), Some(PdfAConformance::PdfA1b));
+        doc.add_page().unwrap();
+
+        // Standard fonts are not embedded, encryption and attachments are
+        // not allowed.
+        let err = doc.get_font("Helvetica", None).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidOperation);
+        assert_eq!(err.api(), Some("HPDF_GetFont"));
+        assert!(doc.set_password("owner", "user").is_err());
+        let err = doc.attach_bytes("a.txt", "text/plain", b"a").unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidOperation);
+        assert_eq!(err.api(), Some("HPDF_AttachFile"));
+        assert!(doc.attach_file("Cargo.toml").is_err());
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("pdfaid:part='1' pdfaid:conformance='B'"));
+        assert!(pdf.contains("<xmp:CreateDate>"));
+        assert!(pdf.contains("/S /GTS_PDFA1"));
+        assert!(pdf.contains("/ID ["));
+
+        // The metadata is written once, and the info dictionary has to match
+        // it from then on.
+        let again = doc.save_to_bytes().unwrap();
+        assert_eq!(again.len(), bytes.len());
+        let again = String::from_utf8_lossy(&again);
+        assert_eq!(again.matches("/Type /Metadata").count(), 1);
+        let err = doc.set_info(InfoAttr::Title, "Late").unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidOperation);
+
+        // PDF/A can't be enabled for documents which break it already.
+        let refused = |setup: &dyn Fn(&PdfDocument)| {
+            let doc = PdfDocument::new();
+            setup(&doc);
+            let profile = doc.load_icc_profile_from_bytes(&[0u8; 128], 3).unwrap();
+            let err = doc
+                .enable_pdfa(PdfAConformance::PdfA1a, "sRGB", &profile)
+                .unwrap_err();
+            assert_eq!(err.kind(), HaruErrorKind::InvalidOperation);
+            assert_eq!(doc.get_pdfa(), None);
+        };
+        refused(&|doc| {
+            doc.set_password("owner", "user").unwrap();
+        });
+        refused(&|doc| {
+            doc.get_font("Helvetica", None).unwrap();
+        });
+        refused(&|doc| {
+            doc.attach_bytes("a.txt", "text/plain", b"a").unwrap();
+        });
+        refused(&|doc| {
+            doc.create_ext_gstate()
+                .unwrap()
+                .set_blend_mode(BlendMode::Multiply)
+                .unwrap();
+        });
+        refused(&|doc| {
+            let image = doc
+                .load_raw_image(1, 1, ColorSpace::DeviceRGB, 8, &[0, 0, 0])
+                .unwrap();
+            let smask = doc
+                .load_raw_image(1, 1, ColorSpace::DeviceGray, 8, &[128])
+                .unwrap();
+            image.add_smask(&smask).unwrap();
+        });
+
+        // Opaque graphics states are fine.
+        let doc = PdfDocument::new();
+        doc.create_ext_gstate()
+            .unwrap()
+            .set_alpha_fill(1.0)
+            .unwrap();
+        let profile = doc.load_icc_profile_from_bytes(&[0u8; 128], 3).unwrap();
+        let other = PdfDocument::new();
+        assert!(other
+            .enable_pdfa(PdfAConformance::PdfA1b, "sRGB", &profile)
+            .is_err());
+        doc.enable_pdfa(PdfAConformance::PdfA1b, "sRGB", &profile)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_icc_profile_from_bytes() {
+        let doc = PdfDocument::new();
+        let profile = doc.load_icc_profile_from_bytes(&[0u8; 128], 4).unwrap();
+        assert_eq!(profile.get_num_components(), 4);
+        doc.add_output_intent("FOGRA39", &profile).unwrap();
+
+        let err = doc.load_icc_profile_from_bytes(&[0u8; 128], 2).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidIccComponentNum);
+
+        let other = PdfDocument::new();
+        assert!(other.add_output_intent("FOGRA39", &profile).is_err());
+
+        doc.add_page().unwrap();
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/Alternate /DeviceCMYK"));
+        assert!(pdf.contains("/OutputConditionIdentifier (FOGRA39)"));
+    }
+
+    /// A 2x3 pixel RGB image.
+    const TEST_PNG: [u8; 73] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x08, 0x02, 0x00, 0x00, 0x00, 0x36,
+        0x88, 0x49, 0xd6, 0x00, 0x00, 0x00, 0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8,
+        0xcf, 0xc0, 0x00, 0x44, 0x0c, 0x28, 0x14, 0x00, 0x44, 0xd0, 0x05, 0xfb, 0xa4, 0xcf, 0xde,
+        0x80, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn test_images_from_bytes() {
+        let doc = PdfDocument::new();
+        let image = doc.load_png_image_from_bytes(&TEST_PNG).unwrap();
+        assert_eq!(image.get_width().unwrap(), 2);
+        assert_eq!(image.get_height().unwrap(), 3);
+
+        let page = doc.add_page().unwrap();
+        page.draw_image(&image, &Rect::new(10.0, 10.0, 20.0, 30.0))
+            .unwrap();
+
+        assert!(doc.load_png_image_from_bytes(b"not a png").is_err());
+        doc.reset_error();
+        let err = doc.load_jpeg_image_from_bytes(b"not a jpeg").unwrap_err();
+        assert_eq!(err.api(), Some("HPDF_LoadJpegImageFromMem"));
+    }
+
+    #[test]
+    fn test_raw_images() {
+        let doc = PdfDocument::new();
+        let rgb = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceRGB, 8, &[0x80; 12])
+            .unwrap();
+        assert_eq!(rgb.get_width().unwrap(), 2);
+        doc.load_raw_image(8, 2, ColorSpace::DeviceCMYK, 4, &[0x0f; 32])
+            .unwrap();
+
+        // 1 bit gray rows are padded to whole bytes. libharu stores them CCITT
+        // G4 encoded, also without image compression.
+        let bitmap = doc
+            .load_raw_image(10, 3, ColorSpace::DeviceGray, 1, &[0xaa; 6])
+            .unwrap();
+        let page = doc.add_page().unwrap();
+        page.draw_image(&bitmap, &Rect::new(10.0, 10.0, 10.0, 3.0))
+            .unwrap();
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert_eq!(pdf.matches("/Filter [ /CCITTFaxDecode ]").count(), 1);
+
+        let err = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceRGB, 8, &[0x80; 11])
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidImage);
+        assert!(doc
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 3, &[0x80; 4])
+            .is_err());
+        assert!(doc
+            .load_raw_image(3, 2, ColorSpace::DeviceGray, 4, &[0x80; 4])
+            .is_err());
+
+        // The ICC profile has to match the color space of the image.
+        let profile = doc.load_icc_profile_from_bytes(&[0u8; 128], 3).unwrap();
+        rgb.set_color_space(&profile).unwrap();
+        let gray = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 8, &[0x80; 4])
+            .unwrap();
+        let err = gray.set_color_space(&profile).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidColorSpace);
+    }
+
+    #[test]
+    fn test_bilevel_images() {
+        let doc = PdfDocument::new();
+        // Rows of 10 pixels padded to 4 bytes.
+        let image = doc
+            .load_raw_1bit_image(10, 3, 4, true, &[0xff; 12])
+            .unwrap();
+        assert_eq!(image.get_width().unwrap(), 10);
+        let err = doc
+            .load_raw_1bit_image(10, 3, 1, true, &[0xff; 3])
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidImage);
+        assert!(doc.load_raw_1bit_image(10, 3, 2, true, &[0xff; 5]).is_err());
+
+        // An all white 8x2 G4 image.
+        let ccitt = doc
+            .load_ccitt_image(
+                8,
+                2,
+                CcittEncoding::Group4,
+                false,
+                true,
+                &[0xc0, 0x04, 0x00, 0x40],
+            )
+            .unwrap();
+        assert_eq!(ccitt.get_height().unwrap(), 2);
+        assert!(doc
+            .load_ccitt_image(8, 2, CcittEncoding::Group3TwoD(0), false, false, &[0x00])
+            .is_err());
+
+        let page = doc.add_page().unwrap();
+        page.draw_image(&image, &Rect::new(10.0, 10.0, 20.0, 20.0))
+            .unwrap();
+        page.draw_image(&ccitt, &Rect::new(30.0, 10.0, 40.0, 20.0))
+            .unwrap();
+        // Saving twice must not duplicate the filter parameters.
+        doc.save_to_bytes().unwrap();
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert_eq!(pdf.matches("/Filter [ /CCITTFaxDecode ]").count(), 2);
+        assert_eq!(pdf.matches("/DecodeParms <<").count(), 2);
+        assert!(pdf.contains("/EncodedByteAlign true"));
+        assert!(!pdf.contains("/BlackIs1"));
+
+        // Raw bits are inverted by the decoder unless they are black, CCITT
+        // data is declared as given.
+        let black_is_1 = |raw: bool, black_is_1: bool| {
+            let doc = PdfDocument::new();
+            let image = match raw {
+                true => doc.load_raw_1bit_image(8, 1, 1, black_is_1, &[0x0f]),
+                false => doc.load_ccitt_image(
+                    8,
+                    2,
+                    CcittEncoding::Group4,
+                    black_is_1,
+                    false,
+                    &[0xc0, 0x04, 0x00, 0x40],
+                ),
+            }
+            .unwrap();
+            doc.add_page()
+                .unwrap()
+                .draw_image(&image, &Rect::new(10.0, 10.0, 20.0, 20.0))
+                .unwrap();
+            let bytes = doc.save_to_bytes().unwrap();
+            String::from_utf8_lossy(&bytes).contains("/BlackIs1 true")
+        };
+        assert!(black_is_1(true, false));
+        assert!(!black_is_1(true, true));
+        assert!(black_is_1(false, true));
+        assert!(!black_is_1(false, false));
+
+        // Invalid arguments are refused before libharu is called.
+        let err = doc
+            .load_ccitt_image(0, 2, CcittEncoding::Group4, false, false, &[0x00])
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidImage);
+        assert_eq!(err.api(), Some("HPDF_DictStream_New"));
+
+        // The image is not created while an error is pending.
+        assert!(doc.get_font("NoSuchFont", None).is_err());
+        assert!(doc
+            .load_ccitt_image(8, 2, CcittEncoding::Group4, false, false, &[0x00])
+            .is_err());
+        doc.reset_error();
+        assert!(doc
+            .load_ccitt_image(8, 2, CcittEncoding::Group4, false, false, &[0x00])
+            .is_ok());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_dynamic_images() {
+        let doc = PdfDocument::new();
+        let rgba = image::RgbaImage::from_pixel(3, 2, image::Rgba([255, 0, 0, 128]));
+        let image = doc
+            .load_dynamic_image(&image::DynamicImage::ImageRgba8(rgba))
+            .unwrap();
+        assert_eq!(image.get_width().unwrap(), 3);
+
+        let luma = image::GrayImage::from_pixel(2, 2, image::Luma([64]));
+        doc.load_dynamic_image(&image::DynamicImage::ImageLuma8(luma))
+            .unwrap();
+
+        // PNG decoding comes with the feature.
+        let png = image::load_from_memory(&TEST_PNG).unwrap();
+        let png = doc.load_dynamic_image(&png).unwrap();
+        assert_eq!(png.get_height().unwrap(), 3);
+
+        let page = doc.add_page().unwrap();
+        page.draw_image(&image, &Rect::new(10.0, 10.0, 30.0, 20.0))
+            .unwrap();
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert_eq!(pdf.matches("/SMask").count(), 1);
+        assert!(pdf.contains("/DeviceRGB"));
+    }
+
+    #[test]
+    fn test_page_labels() {
+        let doc = PdfDocument::new();
+        for _ in 0..4 {
+            doc.add_page().unwrap();
+        }
+        doc.add_page_label(0, PageNumStyle::LowerRoman, 1, None)
+            .unwrap()
+            .add_page_label(2, PageNumStyle::Decimal, 1, Some("A-"))
+            .unwrap();
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/PageLabels"));
+        assert!(pdf.contains("/P (A-)"));
+    }
+
+    #[test]
+    fn test_open_action() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+        let page = doc.add_page().unwrap();
+
+        let dst = page
+            .create_destination(Destination::Xyz {
+                left: 0.0,
+                top: page.get_height(),
+                zoom: 1.5,
+            })
+            .unwrap();
+        doc.set_open_action(&dst).unwrap();
+        dst.set(Destination::FitH { top: 100.0 }).unwrap();
+
+        let err = page
+            .create_destination(Destination::Xyz {
+                left: 0.0,
+                top: 0.0,
+                zoom: 100.0,
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidParameter);
+        assert_eq!(err.api(), Some("HPDF_Destination_SetXYZ"));
     }
 }
@@ -0,0 +1,160 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! The embedded file (attachment) struct and related functions.
+//!
+//! ## Implementation status:
+//!
+//! - [x] HPDF_AttachFile()
+//!
+
+use std::{ffi::CString, ptr};
+
+use crate::{
+    document::PdfDocument,
+    haru_bindings as hb,
+    haru_types::{HaruError, PdfDate},
+};
+
+/// A file embedded into the document. Embedded files are listed in the
+/// attachments panel of the viewer.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct PdfEmbeddedFile<'doc> {
+    /// The file specification dictionary of the embedded file.
+    pub(crate) file: hb::HPDF_EmbeddedFile,
+    /// The document owning the embedded file.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+impl<'doc> PdfEmbeddedFile<'doc> {
+    /// set_description() sets the description shown for the file in the
+    /// attachments panel.
+    ///
+    pub fn set_description(&self, description: &str) -> Result<&Self, HaruError> {
+        let description = CString::new(description).unwrap();
+        let desc_key = CString::new("Desc").unwrap();
+        let result = unsafe {
+            let value =
+                hb::HPDF_String_New((*self.file).mmgr, description.as_ptr(), ptr::null_mut());
+            if value.is_null() {
+                return Err(self.doc.error("HPDF_String_New", 0));
+            }
+            hb::HPDF_Dict_Add(self.file, desc_key.as_ptr(), value as *mut _)
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.doc.error("HPDF_Dict_Add", result)),
+        }
+    }
+
+    /// set_modification_date() sets the date the embedded file was last
+    /// modified.
+    ///
+    pub fn set_modification_date(&self, date: PdfDate) -> Result<&Self, HaruError> {
+        if !date.is_valid() {
+            return Err(
+                HaruError::from(hb::HPDF_INVALID_DATE_TIME as u64).with_api("HPDF_Dict_Add")
+            );
+        }
+
+        let date = CString::new(date.to_pdf_string()).unwrap();
+        let mod_date_key = CString::new("ModDate").unwrap();
+        let result = unsafe {
+            let params = self.params()?;
+            let value = hb::HPDF_String_New((*self.file).mmgr, date.as_ptr(), ptr::null_mut());
+            if value.is_null() {
+                return Err(self.doc.error("HPDF_String_New", 0));
+            }
+            hb::HPDF_Dict_Add(params, mod_date_key.as_ptr(), value as *mut _)
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.doc.error("HPDF_Dict_Add", result)),
+        }
+    }
+
+    /// Returns the Params dictionary of the file stream, creating it if needed.
+    unsafe fn params(&self) -> Result<hb::HPDF_Dict, HaruError> {
+        let ef_key = CString::new("EF").unwrap();
+        let f_key = CString::new("F").unwrap();
+        let params_key = CString::new("Params").unwrap();
+        let dict_class = hb::HPDF_OCLASS_DICT as hb::HPDF_UINT16;
+
+        let ef = hb::HPDF_Dict_GetItem(self.file, ef_key.as_ptr(), dict_class) as hb::HPDF_Dict;
+        let stream = match ef.is_null() {
+            true => ef,
+            false => hb::HPDF_Dict_GetItem(ef, f_key.as_ptr(), dict_class) as hb::HPDF_Dict,
+        };
+        if stream.is_null() {
+            return Err(self.doc.error("HPDF_Dict_GetItem", 0));
+        }
+
+        let params = hb::HPDF_Dict_GetItem(stream, params_key.as_ptr(), dict_class);
+        if !params.is_null() {
+            return Ok(params as hb::HPDF_Dict);
+        }
+
+        let params = hb::HPDF_Dict_New((*self.file).mmgr);
+        if params.is_null() {
+            return Err(self.doc.error("HPDF_Dict_New", 0));
+        }
+        let result = hb::HPDF_Dict_Add(stream, params_key.as_ptr(), params as *mut _);
+        match result {
+            0 => Ok(params),
+            _ => Err(self.doc.error("HPDF_Dict_Add", result)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_description_and_dates() {
+        let doc = PdfDocument::new();
+        doc.add_page().unwrap();
+
+        let invoice = doc
+            .attach_bytes("invoice.xml", "text/xml", b"<Invoice/>")
+            .unwrap();
+        invoice.set_description("The invoice").unwrap();
+        invoice
+            .set_modification_date(PdfDate::new(2024, 1, 2, 3, 4, 5).with_utc_offset(60))
+            .unwrap();
+
+        let manifest = doc.attach_file("Cargo.toml").unwrap();
+        manifest
+            .set_modification_date(PdfDate::new(2024, 1, 2, 3, 4, 5))
+            .unwrap();
+        assert!(manifest
+            .set_modification_date(PdfDate::new(2023, 2, 29, 0, 0, 0))
+            .is_err());
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/Desc (The invoice)"));
+        assert!(pdf.contains("/ModDate (D:20240102030405+01'00')"));
+        assert!(pdf.contains("/ModDate (D:20240102030405)"));
+    }
+}
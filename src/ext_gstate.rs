@@ -0,0 +1,171 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! The extended graphics state struct and related functions.
+//!
+//! ## Implementation status:
+//!
+//! - [x] HPDF_CreateExtGState()
+//! - [x] HPDF_ExtGState_SetAlphaStroke()
+//! - [x] HPDF_ExtGState_SetAlphaFill()
+//! - [x] HPDF_ExtGState_SetBlendMode()
+//!
+
+use crate::{
+    document::PdfDocument,
+    haru_bindings as hb,
+    haru_types::{BlendMode, HaruError},
+};
+
+/// An extended graphics state, holding the transparency settings applied
+/// to a page with `PdfPage::set_ext_gstate()`. Once applied, it can't be
+/// changed anymore.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct PdfExtGState<'doc> {
+    /// The extended graphics state dictionary.
+    pub(crate) gstate: hb::HPDF_ExtGState,
+    /// The document owning the graphics state.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+impl PdfExtGState<'_> {
+    /// set_alpha_fill() sets the opacity used for filling, from 0.0
+    /// (transparent) to 1.0 (opaque).
+    ///
+    /// API: HPDF_ExtGState_SetAlphaFill
+    ///
+    pub fn set_alpha_fill(&self, value: f32) -> Result<&Self, HaruError> {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(HaruError::from(hb::HPDF_EXT_GSTATE_OUT_OF_RANGE as u64)
+                .with_api("HPDF_ExtGState_SetAlphaFill"));
+        }
+        if value < 1.0 {
+            self.doc.ensure_not_pdfa("HPDF_ExtGState_SetAlphaFill")?;
+        }
+        let result = unsafe { hb::HPDF_ExtGState_SetAlphaFill(self.gstate, value) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.doc.error("HPDF_ExtGState_SetAlphaFill", result)),
+        }
+    }
+
+    /// set_alpha_stroke() sets the opacity used for stroking, from 0.0
+    /// (transparent) to 1.0 (opaque).
+    ///
+    /// API: HPDF_ExtGState_SetAlphaStroke
+    ///
+    pub fn set_alpha_stroke(&self, value: f32) -> Result<&Self, HaruError> {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(HaruError::from(hb::HPDF_EXT_GSTATE_OUT_OF_RANGE as u64)
+                .with_api("HPDF_ExtGState_SetAlphaStroke"));
+        }
+        if value < 1.0 {
+            self.doc.ensure_not_pdfa("HPDF_ExtGState_SetAlphaStroke")?;
+        }
+        let result = unsafe { hb::HPDF_ExtGState_SetAlphaStroke(self.gstate, value) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.doc.error("HPDF_ExtGState_SetAlphaStroke", result)),
+        }
+    }
+
+    /// set_blend_mode() sets how painted colors are combined with the colors
+    /// already on the page.
+    ///
+    /// API: HPDF_ExtGState_SetBlendMode
+    ///
+    pub fn set_blend_mode(&self, mode: BlendMode) -> Result<&Self, HaruError> {
+        if mode != BlendMode::Normal {
+            self.doc.ensure_not_pdfa("HPDF_ExtGState_SetBlendMode")?;
+        }
+        let result =
+            unsafe { hb::HPDF_ExtGState_SetBlendMode(self.gstate, mode.to_hpdf_blend_mode()) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.doc.error("HPDF_ExtGState_SetBlendMode", result)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haru_types::{HaruErrorKind, PdfAConformance};
+
+    #[test]
+    fn test_ext_gstate() {
+        let doc = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        let gstate = doc.create_ext_gstate().unwrap();
+        gstate
+            .set_alpha_fill(0.5)
+            .unwrap()
+            .set_alpha_stroke(0.25)
+            .unwrap()
+            .set_blend_mode(BlendMode::Multiply)
+            .unwrap();
+        // Out of range values are refused without an error on the document.
+        let err = gstate.set_alpha_fill(1.5).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::ExtGstateOutOfRange);
+        let err = gstate.set_alpha_stroke(-0.5).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::ExtGstateOutOfRange);
+        assert!(gstate.set_alpha_fill(f32::NAN).is_err());
+        assert!(gstate.set_alpha_stroke(f32::NAN).is_err());
+        assert!(doc.last_error().is_none());
+
+        page.g_save().unwrap();
+        page.set_ext_gstate(&gstate).unwrap();
+        page.rectangle(10.0, 10.0, 100.0, 50.0)
+            .unwrap()
+            .fill()
+            .unwrap();
+        page.g_restore().unwrap();
+
+        // Applied graphics states are read only.
+        let err = gstate.set_alpha_fill(0.1).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::ExtGstateReadOnly);
+        doc.reset_error();
+
+        let other = PdfDocument::new();
+        let foreign = other.create_ext_gstate().unwrap();
+        assert!(page.set_ext_gstate(&foreign).is_err());
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/ca 0.5"));
+        assert!(pdf.contains("/CA 0.25"));
+        assert!(pdf.contains("/BM /Multiply"));
+        assert!(pdf.contains("/ExtGState"));
+
+        // PDF/A-1 doesn't allow transparency.
+        let pdfa = PdfDocument::new();
+        let profile = pdfa.load_icc_profile_from_bytes(&[0u8; 128], 3).unwrap();
+        pdfa.enable_pdfa(PdfAConformance::PdfA1b, "sRGB", &profile)
+            .unwrap();
+        let gstate = pdfa.create_ext_gstate().unwrap();
+        assert!(gstate.set_alpha_fill(0.5).is_err());
+        let err = gstate.set_alpha_stroke(f32::NAN).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::ExtGstateOutOfRange);
+        gstate.set_alpha_fill(1.0).unwrap();
+    }
+}
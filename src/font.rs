@@ -38,17 +38,62 @@
 //! - [ ] HPDF_Font_MeasureText()
 //!
 
-use crate::{haru_bindings as hb, HpdfBox};
+use crate::{document::PdfDocument, haru_bindings as hb, HpdfBox};
 
-/// The font object.
+/// The font object. It borrows the document it was loaded into.
 ///
 #[derive(Debug, Copy, Clone)]
-pub struct PdfFont {
+pub struct PdfFont<'doc> {
     /// The reference to the haru font.
-    pub font_ref: hb::HPDF_Font,
+    pub(crate) font_ref: hb::HPDF_Font,
+    /// The document owning the font.
+    pub(crate) doc: &'doc PdfDocument,
 }
 
-impl PdfFont {
+/// The names of the standard 14 fonts, which libharu never embeds.
+const BASE14_FONTS: [&str; 14] = [
+    "Courier",
+    "Courier-Bold",
+    "Courier-Oblique",
+    "Courier-BoldOblique",
+    "Helvetica",
+    "Helvetica-Bold",
+    "Helvetica-Oblique",
+    "Helvetica-BoldOblique",
+    "Times-Roman",
+    "Times-Bold",
+    "Times-Italic",
+    "Times-BoldItalic",
+    "Symbol",
+    "ZapfDingbats",
+];
+
+/// Returns true if fonts of the given definition embed the font program.
+/// The standard 14 fonts and the built-in CJK fonts are never embedded.
+pub(crate) unsafe fn is_fontdef_embedded(fontdef: hb::HPDF_FontDef) -> bool {
+    match (*fontdef).type_ {
+        hb::_HPDF_FontDefType_HPDF_FONTDEF_TYPE_TRUETYPE => {
+            (*((*fontdef).attr as hb::HPDF_TTFontDefAttr)).embedding != 0
+        }
+        hb::_HPDF_FontDefType_HPDF_FONTDEF_TYPE_TYPE1 => {
+            let name = std::ffi::CStr::from_ptr((*fontdef).base_font.as_ptr());
+            !BASE14_FONTS
+                .iter()
+                .any(|base14| name.to_bytes() == base14.as_bytes())
+        }
+        _ => false,
+    }
+}
+
+impl PdfFont<'_> {
+    /// Returns true if the font program is embedded into the document.
+    pub(crate) fn is_embedded(&self) -> bool {
+        unsafe {
+            let attr = (*self.font_ref).attr as hb::HPDF_FontAttr;
+            is_fontdef_embedded((*attr).fontdef)
+        }
+    }
+
     /// HPDF_Font_GetFontName() gets the name of the font.
     ///
     pub fn get_font_name(&self) -> String {
@@ -85,7 +130,7 @@ impl PdfFont {
     /// When HPDF_Font_GetBox() succeed, it returns the HPDF_Box struct specifying the font bounding box. Otherwise, it returns a HPDF_Box struct of {0, 0, 0, 0}.
     ///
     /// API: HPDF_Box HPDF_Font_GetBBox
-
+    ///
     pub fn get_b_box(&self) -> HpdfBox {
         unsafe { HpdfBox::from(hb::HPDF_Font_GetBBox(self.font_ref)) }
     }
@@ -23,6 +23,8 @@
 //! This module contains types that are used in the library.
 //!
 
+use std::sync::Arc;
+
 use crate::haru_bindings as hb;
 
 pub enum PageMode {
@@ -66,6 +68,10 @@ pub enum PageLayout {
     TwoColumnLeft,
     ///  Display in two columns. Odd page number is displayed right.
     TwoColumnRight,
+    /// Display the pages two at a time. Odd page number is displayed left.
+    TwoPageLeft,
+    /// Display the pages two at a time. Odd page number is displayed right.
+    TwoPageRight,
 }
 
 impl PageLayout {
@@ -75,6 +81,8 @@ impl PageLayout {
             PageLayout::OneColumn => hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_ONE_COLUMN,
             PageLayout::TwoColumnLeft => hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_COLUMN_LEFT,
             PageLayout::TwoColumnRight => hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_COLUMN_RIGHT,
+            PageLayout::TwoPageLeft => hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_PAGE_LEFT,
+            PageLayout::TwoPageRight => hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_PAGE_RIGHT,
         }
     }
 
@@ -84,16 +92,333 @@ impl PageLayout {
             hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_ONE_COLUMN => PageLayout::OneColumn,
             hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_COLUMN_LEFT => PageLayout::TwoColumnLeft,
             hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_COLUMN_RIGHT => PageLayout::TwoColumnRight,
+            hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_PAGE_LEFT => PageLayout::TwoPageLeft,
+            hb::_HPDF_PageLayout_HPDF_PAGE_LAYOUT_TWO_PAGE_RIGHT => PageLayout::TwoPageRight,
             _ => PageLayout::Single,
         }
     }
 }
 
+/// The viewer preferences of a document, controlling the viewer's user
+/// interface when the document is opened.
+///
+/// Preferences can be combined using the `|` operator, e.g.
+/// `ViewerPreferences::HIDE_TOOLBAR | ViewerPreferences::FIT_WINDOW`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ViewerPreferences(hb::HPDF_UINT);
+
+impl ViewerPreferences {
+    /// Hide the viewer's toolbars.
+    pub const HIDE_TOOLBAR: ViewerPreferences = ViewerPreferences(hb::HPDF_HIDE_TOOLBAR);
+    /// Hide the viewer's menu bar.
+    pub const HIDE_MENUBAR: ViewerPreferences = ViewerPreferences(hb::HPDF_HIDE_MENUBAR);
+    /// Hide user interface elements like scroll bars and navigation controls.
+    pub const HIDE_WINDOW_UI: ViewerPreferences = ViewerPreferences(hb::HPDF_HIDE_WINDOW_UI);
+    /// Resize the document's window to fit the size of the first page.
+    pub const FIT_WINDOW: ViewerPreferences = ViewerPreferences(hb::HPDF_FIT_WINDOW);
+    /// Position the document's window in the center of the screen.
+    pub const CENTER_WINDOW: ViewerPreferences = ViewerPreferences(hb::HPDF_CENTER_WINDOW);
+    /// Disable the print scaling of the print dialog.
+    pub const PRINT_SCALING_NONE: ViewerPreferences =
+        ViewerPreferences(hb::HPDF_PRINT_SCALING_NONE);
+
+    /// Returns true if all preferences in `other` are also set in `self`.
+    ///
+    pub fn contains(&self, other: ViewerPreferences) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn to_hpdf_viewer_preference(&self) -> hb::HPDF_UINT {
+        self.0
+    }
+
+    pub fn from_hpdf_viewer_preference(value: hb::HPDF_UINT) -> Self {
+        ViewerPreferences(value)
+    }
+}
+
+impl std::ops::BitOr for ViewerPreferences {
+    type Output = ViewerPreferences;
+
+    fn bitor(self, rhs: ViewerPreferences) -> ViewerPreferences {
+        ViewerPreferences(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for ViewerPreferences {
+    fn bitor_assign(&mut self, rhs: ViewerPreferences) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The boundary boxes of a page.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryKind {
+    /// The boundaries of the physical medium the page is displayed or
+    /// printed on.
+    MediaBox,
+    /// The region the page contents are clipped to when displayed or printed.
+    CropBox,
+    /// The region the page contents are clipped to in a production
+    /// environment, including the bleed area.
+    BleedBox,
+    /// The intended dimensions of the finished page after trimming.
+    TrimBox,
+    /// The extent of the page's meaningful content.
+    ArtBox,
+}
+
+impl BoundaryKind {
+    pub fn to_hpdf_page_boundary(&self) -> hb::HPDF_PageBoundary {
+        match self {
+            BoundaryKind::MediaBox => hb::_HPDF_PageBoundary_HPDF_PAGE_MEDIABOX,
+            BoundaryKind::CropBox => hb::_HPDF_PageBoundary_HPDF_PAGE_CROPBOX,
+            BoundaryKind::BleedBox => hb::_HPDF_PageBoundary_HPDF_PAGE_BLEEDBOX,
+            BoundaryKind::TrimBox => hb::_HPDF_PageBoundary_HPDF_PAGE_TRIMBOX,
+            BoundaryKind::ArtBox => hb::_HPDF_PageBoundary_HPDF_PAGE_ARTBOX,
+        }
+    }
+
+    /// The key of the box in the page dictionary.
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            BoundaryKind::MediaBox => "MediaBox",
+            BoundaryKind::CropBox => "CropBox",
+            BoundaryKind::BleedBox => "BleedBox",
+            BoundaryKind::TrimBox => "TrimBox",
+            BoundaryKind::ArtBox => "ArtBox",
+        }
+    }
+}
+
+/// The predefined page sizes.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageSize {
+    /// 8.5 x 11 inches (612 x 792 points).
+    Letter,
+    /// 8.5 x 14 inches (612 x 1008 points).
+    Legal,
+    /// 297 x 420 mm (841.89 x 1190.551 points).
+    A3,
+    /// 210 x 297 mm (595.276 x 841.89 points).
+    A4,
+    /// 148 x 210 mm (419.528 x 595.276 points).
+    A5,
+    /// 250 x 353 mm (708.661 x 1000.63 points).
+    B4,
+    /// 176 x 250 mm (498.898 x 708.661 points).
+    B5,
+    /// 7.25 x 10.5 inches (522 x 756 points).
+    Executive,
+    /// 4 x 6 inches (288 x 432 points).
+    US4x6,
+    /// 4 x 8 inches (288 x 576 points).
+    US4x8,
+    /// 5 x 7 inches (360 x 504 points).
+    US5x7,
+    /// 4.125 x 9.5 inches, envelope No. 10 (297 x 684 points).
+    Comm10,
+}
+
+impl PageSize {
+    pub fn to_hpdf_page_size(&self) -> hb::HPDF_PageSizes {
+        match self {
+            PageSize::Letter => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_LETTER,
+            PageSize::Legal => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_LEGAL,
+            PageSize::A3 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_A3,
+            PageSize::A4 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_A4,
+            PageSize::A5 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_A5,
+            PageSize::B4 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_B4,
+            PageSize::B5 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_B5,
+            PageSize::Executive => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_EXECUTIVE,
+            PageSize::US4x6 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_US4x6,
+            PageSize::US4x8 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_US4x8,
+            PageSize::US5x7 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_US5x7,
+            PageSize::Comm10 => hb::_HPDF_PageSizes_HPDF_PAGE_SIZE_COMM10,
+        }
+    }
+}
+
+/// The orientation of a page.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageDirection {
+    /// The longer side of the page is vertical.
+    Portrait,
+    /// The longer side of the page is horizontal.
+    Landscape,
+}
+
+impl PageDirection {
+    pub fn to_hpdf_page_direction(&self) -> hb::HPDF_PageDirection {
+        match self {
+            PageDirection::Portrait => hb::_HPDF_PageDirection_HPDF_PAGE_PORTRAIT,
+            PageDirection::Landscape => hb::_HPDF_PageDirection_HPDF_PAGE_LANDSCAPE,
+        }
+    }
+}
+
+/// The device color spaces of raw images.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// One gray component per pixel.
+    DeviceGray,
+    /// Red, green and blue components per pixel.
+    DeviceRGB,
+    /// Cyan, magenta, yellow and black components per pixel.
+    DeviceCMYK,
+}
+
+impl ColorSpace {
+    pub fn to_hpdf_color_space(&self) -> hb::HPDF_ColorSpace {
+        match self {
+            ColorSpace::DeviceGray => hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_GRAY,
+            ColorSpace::DeviceRGB => hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_RGB,
+            ColorSpace::DeviceCMYK => hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_CMYK,
+        }
+    }
+
+    /// Returns the device color space, or None for other color spaces.
+    ///
+    pub fn from_hpdf_color_space(color_space: hb::HPDF_ColorSpace) -> Option<Self> {
+        match color_space {
+            hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_GRAY => Some(ColorSpace::DeviceGray),
+            hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_RGB => Some(ColorSpace::DeviceRGB),
+            hb::_HPDF_ColorSpace_HPDF_CS_DEVICE_CMYK => Some(ColorSpace::DeviceCMYK),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of color components per pixel.
+    ///
+    pub fn num_components(&self) -> u32 {
+        match self {
+            ColorSpace::DeviceGray => 1,
+            ColorSpace::DeviceRGB => 3,
+            ColorSpace::DeviceCMYK => 4,
+        }
+    }
+}
+
+/// The blend modes of an extended graphics state. They define how painted
+/// colors are combined with the colors already on the page.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+}
+
+impl BlendMode {
+    pub fn to_hpdf_blend_mode(&self) -> hb::HPDF_BlendMode {
+        match self {
+            BlendMode::Normal => hb::_HPDF_BlendMode_HPDF_BM_NORMAL,
+            BlendMode::Multiply => hb::_HPDF_BlendMode_HPDF_BM_MULTIPLY,
+            BlendMode::Screen => hb::_HPDF_BlendMode_HPDF_BM_SCREEN,
+            BlendMode::Overlay => hb::_HPDF_BlendMode_HPDF_BM_OVERLAY,
+            BlendMode::Darken => hb::_HPDF_BlendMode_HPDF_BM_DARKEN,
+            BlendMode::Lighten => hb::_HPDF_BlendMode_HPDF_BM_LIGHTEN,
+            BlendMode::ColorDodge => hb::_HPDF_BlendMode_HPDF_BM_COLOR_DODGE,
+            BlendMode::ColorBurn => hb::_HPDF_BlendMode_HPDF_BM_COLOR_BUM,
+            BlendMode::HardLight => hb::_HPDF_BlendMode_HPDF_BM_HARD_LIGHT,
+            BlendMode::SoftLight => hb::_HPDF_BlendMode_HPDF_BM_SOFT_LIGHT,
+            BlendMode::Difference => hb::_HPDF_BlendMode_HPDF_BM_DIFFERENCE,
+            BlendMode::Exclusion => hb::_HPDF_BlendMode_HPDF_BM_EXCLUSHON,
+        }
+    }
+}
+
+/// The encodings of CCITT fax compressed bilevel images.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CcittEncoding {
+    /// Group 3, one-dimensional encoding.
+    Group3OneD,
+    /// Group 3, mixed one- and two-dimensional encoding. A one-dimensionally
+    /// encoded line is followed by at most `k - 1` two-dimensionally encoded
+    /// lines.
+    Group3TwoD(u32),
+    /// Group 4, pure two-dimensional encoding.
+    Group4,
+}
+
+impl CcittEncoding {
+    /// Returns the value of the K parameter of the CCITTFaxDecode filter.
+    pub(crate) fn k(&self) -> i32 {
+        match self {
+            CcittEncoding::Group3OneD => 0,
+            CcittEncoding::Group3TwoD(k) => (*k).min(i32::MAX as u32) as i32,
+            CcittEncoding::Group4 => -1,
+        }
+    }
+}
+
+/// The PDF/A conformance level of a document.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfAConformance {
+    /// PDF/A-1a, level A (accessible).
+    PdfA1a,
+    /// PDF/A-1b, level B (basic).
+    PdfA1b,
+}
+
+impl PdfAConformance {
+    pub fn to_hpdf_pdfa_type(&self) -> hb::HPDF_PDFAType {
+        match self {
+            PdfAConformance::PdfA1a => hb::_HPDF_PDFA_TYPE_HPDF_PDFA_1A,
+            PdfAConformance::PdfA1b => hb::_HPDF_PDFA_TYPE_HPDF_PDFA_1B,
+        }
+    }
+}
+
+/// The numbering style of a page label.
+///
+pub enum PageNumStyle {
+    /// Arabic numerals (1 2 3 4).
+    Decimal,
+    /// Uppercase roman numerals (I II III IV).
+    UpperRoman,
+    /// Lowercase roman numerals (i ii iii iv).
+    LowerRoman,
+    /// Uppercase letters (A B C D).
+    UpperLetters,
+    /// Lowercase letters (a b c d).
+    LowerLetters,
+}
+
+impl PageNumStyle {
+    pub fn to_hpdf_page_num_style(&self) -> hb::HPDF_PageNumStyle {
+        match self {
+            PageNumStyle::Decimal => hb::_HPDF_PageNumStyle_HPDF_PAGE_NUM_STYLE_DECIMAL,
+            PageNumStyle::UpperRoman => hb::_HPDF_PageNumStyle_HPDF_PAGE_NUM_STYLE_UPPER_ROMAN,
+            PageNumStyle::LowerRoman => hb::_HPDF_PageNumStyle_HPDF_PAGE_NUM_STYLE_LOWER_ROMAN,
+            PageNumStyle::UpperLetters => hb::_HPDF_PageNumStyle_HPDF_PAGE_NUM_STYLE_UPPER_LETTERS,
+            PageNumStyle::LowerLetters => hb::_HPDF_PageNumStyle_HPDF_PAGE_NUM_STYLE_LOWER_LETTERS,
+        }
+    }
+}
+
 pub enum InfoAttr {
     CreationDate,
     ModDate,
     Author,
     Creator,
+    Producer,
     Title,
     Subject,
     Keywords,
@@ -106,6 +431,7 @@ impl InfoAttr {
             InfoAttr::ModDate => hb::_HPDF_InfoType_HPDF_INFO_MOD_DATE,
             InfoAttr::Author => hb::_HPDF_InfoType_HPDF_INFO_AUTHOR,
             InfoAttr::Creator => hb::_HPDF_InfoType_HPDF_INFO_CREATOR,
+            InfoAttr::Producer => hb::_HPDF_InfoType_HPDF_INFO_PRODUCER,
             InfoAttr::Title => hb::_HPDF_InfoType_HPDF_INFO_TITLE,
             InfoAttr::Subject => hb::_HPDF_InfoType_HPDF_INFO_SUBJECT,
             InfoAttr::Keywords => hb::_HPDF_InfoType_HPDF_INFO_KEYWORDS,
@@ -113,6 +439,132 @@ impl InfoAttr {
     }
 }
 
+/// A date for the CreationDate and ModDate entries of the document info dictionary.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfDate {
+    /// The year, e.g. 2024.
+    pub year: i32,
+    /// The month, between 1 and 12.
+    pub month: i32,
+    /// The day of the month, between 1 and 31.
+    pub day: i32,
+    /// The hour, between 0 and 23.
+    pub hour: i32,
+    /// The minutes, between 0 and 59.
+    pub minutes: i32,
+    /// The seconds, between 0 and 59.
+    pub seconds: i32,
+    /// The offset from UTC in minutes. `None` leaves the timezone unspecified,
+    /// `Some(0)` marks the date as UTC.
+    pub utc_offset: Option<i32>,
+}
+
+impl PdfDate {
+    /// Create a date without timezone information.
+    ///
+    pub fn new(year: i32, month: i32, day: i32, hour: i32, minutes: i32, seconds: i32) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minutes,
+            seconds,
+            utc_offset: None,
+        }
+    }
+
+    /// Create a date for the current time in UTC.
+    ///
+    pub fn now() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let (days, secs) = (secs.div_euclid(86400), secs.rem_euclid(86400) as i32);
+
+        // Convert days since 1970-01-01 to a civil date (proleptic Gregorian calendar).
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as i32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as i32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+        Self::new(year, month, day, secs / 3600, secs / 60 % 60, secs % 60).with_utc_offset(0)
+    }
+
+    /// Set the offset from UTC in minutes, e.g. `with_utc_offset(60)` for CET
+    /// or `with_utc_offset(-(5 * 60 + 30))` for UTC-05:30. Offsets of a day
+    /// or more are rejected when the date is set.
+    ///
+    pub fn with_utc_offset(mut self, minutes: i32) -> Self {
+        self.utc_offset = Some(minutes);
+        self
+    }
+
+    pub fn to_hpdf_date(&self) -> hb::HPDF_Date {
+        let (ind, offset) = match self.utc_offset {
+            None => (b' ', 0),
+            Some(0) => (b'Z', 0),
+            Some(offset) if offset > 0 => (b'+', offset),
+            Some(offset) => (b'-', -offset),
+        };
+
+        hb::HPDF_Date {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+            hour: self.hour,
+            minutes: self.minutes,
+            seconds: self.seconds,
+            ind: ind as std::os::raw::c_char,
+            off_hour: offset / 60,
+            off_minutes: offset % 60,
+        }
+    }
+
+    /// Returns true if the date passes the same checks libharu applies to
+    /// the dates of the info dictionary.
+    pub(crate) fn is_valid(&self) -> bool {
+        let leap = self.year % 4 == 0 && (self.year % 100 != 0 || self.year % 400 == 0);
+        let days = match self.month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return false,
+        };
+
+        (1..=days).contains(&self.day)
+            && (0..=23).contains(&self.hour)
+            && (0..=59).contains(&self.minutes)
+            && (0..=59).contains(&self.seconds)
+            && !matches!(self.utc_offset, Some(offset) if offset.abs() >= 24 * 60)
+    }
+
+    /// Formats the date as a PDF date string, e.g. `D:20240102030405+01'00'`.
+    pub(crate) fn to_pdf_string(self) -> String {
+        let date = format!(
+            "D:{:04}{:02}{:02}{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minutes, self.seconds
+        );
+        match self.utc_offset {
+            None => date,
+            Some(0) => format!("{date}Z"),
+            Some(offset) => {
+                let sign = if offset > 0 { '+' } else { '-' };
+                let offset = offset.abs();
+                format!("{date}{sign}{:02}'{:02}'", offset / 60, offset % 60)
+            }
+        }
+    }
+}
+
 pub enum CompressionMode {
     /// No compression.
     None,
@@ -138,10 +590,105 @@ impl CompressionMode {
     }
 }
 
-/// The error type for libharu.
+/// The set of operations a user is allowed to perform on an encrypted document.
+///
+/// Permissions can be combined using the `|` operator, e.g.
+/// `Permissions::PRINT | Permissions::COPY`.
 ///
-#[derive(Debug)]
-pub enum HaruError {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Permissions(hb::HPDF_UINT);
+
+impl Permissions {
+    /// The user may print the document.
+    pub const PRINT: Permissions = Permissions(hb::HPDF_ENABLE_PRINT);
+    /// The user may edit the contents of the document other than annotations and form fields.
+    pub const EDIT: Permissions = Permissions(hb::HPDF_ENABLE_EDIT_ALL);
+    /// The user may copy text and graphics from the document.
+    pub const COPY: Permissions = Permissions(hb::HPDF_ENABLE_COPY);
+    /// The user may add or modify annotations and form fields.
+    pub const EDIT_ANNOTATIONS: Permissions = Permissions(hb::HPDF_ENABLE_EDIT);
+
+    /// Returns the empty set: the user may only read the document.
+    ///
+    pub const fn empty() -> Permissions {
+        Permissions(0)
+    }
+
+    /// Returns true if all permissions in `other` are also set in `self`.
+    ///
+    pub fn contains(&self, other: Permissions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn to_hpdf_permission(&self) -> hb::HPDF_UINT {
+        self.0
+    }
+}
+
+impl std::ops::BitOr for Permissions {
+    type Output = Permissions;
+
+    fn bitor(self, rhs: Permissions) -> Permissions {
+        Permissions(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Permissions {
+    fn bitor_assign(&mut self, rhs: Permissions) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The encryption mode of a password protected document.
+///
+pub enum EncryptionMode {
+    /// Revision 2 with a 40-bit key (PDF 1.3 compatible).
+    R2,
+    /// Revision 3 with a key length between 5 and 16 bytes (40 to 128 bits).
+    /// The document is upgraded to PDF 1.4.
+    R3 { key_len: u32 },
+}
+
+impl EncryptionMode {
+    pub fn to_hpdf_encrypt_mode(&self) -> (hb::HPDF_EncryptMode, hb::HPDF_UINT) {
+        match self {
+            EncryptionMode::R2 => (hb::_HPDF_EncryptMode_HPDF_ENCRYPT_R2, 5),
+            EncryptionMode::R3 { key_len } => (hb::_HPDF_EncryptMode_HPDF_ENCRYPT_R3, *key_len),
+        }
+    }
+}
+
+/// How a page is displayed when a destination is opened.
+///
+pub enum Destination {
+    /// Display the page with (left, top) at the upper-left corner of the window,
+    /// magnified by zoom (between 0.08 and 32).
+    Xyz { left: f32, top: f32, zoom: f32 },
+    /// Fit the entire page within the window.
+    Fit,
+    /// Fit the width of the page within the window, with top at the top edge.
+    FitH { top: f32 },
+    /// Fit the height of the page within the window, with left at the left edge.
+    FitV { left: f32 },
+    /// Fit the given rectangle within the window.
+    FitR {
+        left: f32,
+        bottom: f32,
+        right: f32,
+        top: f32,
+    },
+    /// Fit the bounding box of the page contents within the window.
+    FitB,
+    /// Fit the width of the bounding box within the window, with top at the top edge.
+    FitBH { top: f32 },
+    /// Fit the height of the bounding box within the window, with left at the left edge.
+    FitBV { left: f32 },
+}
+
+/// The error codes reported by libharu.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaruErrorKind {
     ArrayCountErr,
     ArrayItemNotFound,
     ArrayItemUnexpectedType,
@@ -254,125 +801,299 @@ pub enum HaruError {
     UnknownError,
 }
 
-impl From<u64> for HaruError {
+impl From<u64> for HaruErrorKind {
     fn from(code: u64) -> Self {
         match code {
-            0x1001 => HaruError::ArrayCountErr,
-            0x1002 => HaruError::ArrayItemNotFound,
-            0x1003 => HaruError::ArrayItemUnexpectedType,
-            0x1004 => HaruError::BinaryLengthErr,
-            0x1005 => HaruError::CannotGetPallet,
-            0x1007 => HaruError::DictCountErr,
-            0x1008 => HaruError::DictItemNotFound,
-            0x1009 => HaruError::DictItemUnexpectedType,
-            0x100A => HaruError::DictStreamLengthNotFound,
-            0x100B => HaruError::DocEncryptDictNotFound,
-            0x100C => HaruError::DocInvalidObject,
-            0x100E => HaruError::DuplicateRegistration,
-            0x100F => HaruError::ExceedJwwCodeNumLimit,
-            0x1011 => HaruError::EncryptInvalidPassword,
-            0x1013 => HaruError::ErrUnknownClass,
-            0x1014 => HaruError::ExceedGstateLimit,
-            0x1015 => HaruError::FaildToAllocMem,
-            0x1016 => HaruError::FileIoError,
-            0x1017 => HaruError::FileOpenError,
-            0x1019 => HaruError::FontExists,
-            0x101A => HaruError::FontInvalidWidthsTable,
-            0x101B => HaruError::InvalidAfmHeader,
-            0x101C => HaruError::InvalidAnnotation,
-            0x101E => HaruError::InvalidBitPerComponent,
-            0x101F => HaruError::InvalidCharMatricsData,
-            0x1020 => HaruError::InvalidColorSpace,
-            0x1021 => HaruError::InvalidCompressionMode,
-            0x1022 => HaruError::InvalidDateTime,
-            0x1023 => HaruError::InvalidDestination,
-            0x1025 => HaruError::InvalidDocument,
-            0x1026 => HaruError::InvalidDocumentState,
-            0x1027 => HaruError::InvalidEncoder,
-            0x1028 => HaruError::InvalidEncoderType,
-            0x102B => HaruError::InvalidEncodingName,
-            0x102C => HaruError::InvalidEncryptKeyLen,
-            0x102D => HaruError::InvalidFontdefData,
-            0x102E => HaruError::InvalidFontdefType,
-            0x102F => HaruError::InvalidFontName,
-            0x1030 => HaruError::InvalidImage,
-            0x1031 => HaruError::InvalidJpegData,
-            0x1032 => HaruError::InvalidNData,
-            0x1033 => HaruError::InvalidObject,
-            0x1034 => HaruError::InvalidObjId,
-            0x1035 => HaruError::InvalidOperation,
-            0x1036 => HaruError::InvalidOutline,
-            0x1037 => HaruError::InvalidPage,
-            0x1038 => HaruError::InvalidPages,
-            0x1039 => HaruError::InvalidParameter,
-            0x103B => HaruError::InvalidPngImage,
-            0x103C => HaruError::InvalidStream,
-            0x103D => HaruError::MissingFileNameEntry,
-            0x103F => HaruError::InvalidTtcFile,
-            0x1040 => HaruError::InvalidTtcIndex,
-            0x1041 => HaruError::InvalidWxData,
-            0x1042 => HaruError::ItemNotFound,
-            0x1043 => HaruError::LibpngError,
-            0x1044 => HaruError::NameInvalidValue,
-            0x1045 => HaruError::NameOutOfRange,
-            0x1048 => HaruError::PageInvalidParamCount,
-            0x1049 => HaruError::PagesMissingKidsEntry,
-            0x104A => HaruError::PageCannotFindObject,
-            0x104B => HaruError::PageCannotGetRootPages,
-            0x104C => HaruError::PageCannotRestoreGstate,
-            0x104D => HaruError::PageCannotSetParent,
-            0x104E => HaruError::PageFontNotFound,
-            0x104F => HaruError::PageInvalidFont,
-            0x1050 => HaruError::PageInvalidFontSize,
-            0x1051 => HaruError::PageInvalidGmode,
-            0x1052 => HaruError::PageInvalidIndex,
-            0x1053 => HaruError::PageInvalidRotateValue,
-            0x1054 => HaruError::PageInvalidSize,
-            0x1055 => HaruError::PageInvalidXobject,
-            0x1056 => HaruError::PageOutOfRange,
-            0x1057 => HaruError::RealOutOfRange,
-            0x1058 => HaruError::StreamEof,
-            0x1059 => HaruError::StreamReadlnContinue,
-            0x105B => HaruError::StringOutOfRange,
-            0x105C => HaruError::ThisFuncWasSkipped,
-            0x105D => HaruError::TtfCannotEmbeddingFont,
-            0x105E => HaruError::TtfInvalidCmap,
-            0x105F => HaruError::TtfInvalidFomat,
-            0x1060 => HaruError::TtfMissingTable,
-            0x1061 => HaruError::UnsupportedFontType,
-            0x1062 => HaruError::UnsupportedFunc,
-            0x1063 => HaruError::UnsupportedJpegFormat,
-            0x1064 => HaruError::UnsupportedType1Font,
-            0x1065 => HaruError::XrefCountErr,
-            0x1066 => HaruError::ZlibError,
-            0x1067 => HaruError::InvalidPageIndex,
-            0x1068 => HaruError::InvalidUri,
-            0x1069 => HaruError::PageLayoutOutOfRange,
-            0x1070 => HaruError::PageModeOutOfRange,
-            0x1071 => HaruError::PageNumStyleOutOfRange,
-            0x1072 => HaruError::AnnotInvalidIcon,
-            0x1073 => HaruError::AnnotInvalidBorderStyle,
-            0x1074 => HaruError::PageInvalidDirection,
-            0x1075 => HaruError::InvalidFont,
-            0x1076 => HaruError::PageInsufficientSpace,
-            0x1077 => HaruError::PageInvalidDisplayTime,
-            0x1078 => HaruError::PageInvalidTransitionTime,
-            0x1079 => HaruError::InvalidPageSlideshowType,
-            0x1080 => HaruError::ExtGstateOutOfRange,
-            0x1081 => HaruError::InvalidExtGstate,
-            0x1082 => HaruError::ExtGstateReadOnly,
-            0x1083 => HaruError::InvalidU3dData,
-            0x1084 => HaruError::NameCannotGetNames,
-            0x1085 => HaruError::InvalidIccComponentNum,
-            0x1086 => HaruError::PageInvalidBoundary,
-            0x1088 => HaruError::PageInvalidShadingType,
-            _ => HaruError::UnknownError,
+            0x1001 => HaruErrorKind::ArrayCountErr,
+            0x1002 => HaruErrorKind::ArrayItemNotFound,
+            0x1003 => HaruErrorKind::ArrayItemUnexpectedType,
+            0x1004 => HaruErrorKind::BinaryLengthErr,
+            0x1005 => HaruErrorKind::CannotGetPallet,
+            0x1007 => HaruErrorKind::DictCountErr,
+            0x1008 => HaruErrorKind::DictItemNotFound,
+            0x1009 => HaruErrorKind::DictItemUnexpectedType,
+            0x100A => HaruErrorKind::DictStreamLengthNotFound,
+            0x100B => HaruErrorKind::DocEncryptDictNotFound,
+            0x100C => HaruErrorKind::DocInvalidObject,
+            0x100E => HaruErrorKind::DuplicateRegistration,
+            0x100F => HaruErrorKind::ExceedJwwCodeNumLimit,
+            0x1011 => HaruErrorKind::EncryptInvalidPassword,
+            0x1013 => HaruErrorKind::ErrUnknownClass,
+            0x1014 => HaruErrorKind::ExceedGstateLimit,
+            0x1015 => HaruErrorKind::FaildToAllocMem,
+            0x1016 => HaruErrorKind::FileIoError,
+            0x1017 => HaruErrorKind::FileOpenError,
+            0x1019 => HaruErrorKind::FontExists,
+            0x101A => HaruErrorKind::FontInvalidWidthsTable,
+            0x101B => HaruErrorKind::InvalidAfmHeader,
+            0x101C => HaruErrorKind::InvalidAnnotation,
+            0x101E => HaruErrorKind::InvalidBitPerComponent,
+            0x101F => HaruErrorKind::InvalidCharMatricsData,
+            0x1020 => HaruErrorKind::InvalidColorSpace,
+            0x1021 => HaruErrorKind::InvalidCompressionMode,
+            0x1022 => HaruErrorKind::InvalidDateTime,
+            0x1023 => HaruErrorKind::InvalidDestination,
+            0x1025 => HaruErrorKind::InvalidDocument,
+            0x1026 => HaruErrorKind::InvalidDocumentState,
+            0x1027 => HaruErrorKind::InvalidEncoder,
+            0x1028 => HaruErrorKind::InvalidEncoderType,
+            0x102B => HaruErrorKind::InvalidEncodingName,
+            0x102C => HaruErrorKind::InvalidEncryptKeyLen,
+            0x102D => HaruErrorKind::InvalidFontdefData,
+            0x102E => HaruErrorKind::InvalidFontdefType,
+            0x102F => HaruErrorKind::InvalidFontName,
+            0x1030 => HaruErrorKind::InvalidImage,
+            0x1031 => HaruErrorKind::InvalidJpegData,
+            0x1032 => HaruErrorKind::InvalidNData,
+            0x1033 => HaruErrorKind::InvalidObject,
+            0x1034 => HaruErrorKind::InvalidObjId,
+            0x1035 => HaruErrorKind::InvalidOperation,
+            0x1036 => HaruErrorKind::InvalidOutline,
+            0x1037 => HaruErrorKind::InvalidPage,
+            0x1038 => HaruErrorKind::InvalidPages,
+            0x1039 => HaruErrorKind::InvalidParameter,
+            0x103B => HaruErrorKind::InvalidPngImage,
+            0x103C => HaruErrorKind::InvalidStream,
+            0x103D => HaruErrorKind::MissingFileNameEntry,
+            0x103F => HaruErrorKind::InvalidTtcFile,
+            0x1040 => HaruErrorKind::InvalidTtcIndex,
+            0x1041 => HaruErrorKind::InvalidWxData,
+            0x1042 => HaruErrorKind::ItemNotFound,
+            0x1043 => HaruErrorKind::LibpngError,
+            0x1044 => HaruErrorKind::NameInvalidValue,
+            0x1045 => HaruErrorKind::NameOutOfRange,
+            0x1048 => HaruErrorKind::PageInvalidParamCount,
+            0x1049 => HaruErrorKind::PagesMissingKidsEntry,
+            0x104A => HaruErrorKind::PageCannotFindObject,
+            0x104B => HaruErrorKind::PageCannotGetRootPages,
+            0x104C => HaruErrorKind::PageCannotRestoreGstate,
+            0x104D => HaruErrorKind::PageCannotSetParent,
+            0x104E => HaruErrorKind::PageFontNotFound,
+            0x104F => HaruErrorKind::PageInvalidFont,
+            0x1050 => HaruErrorKind::PageInvalidFontSize,
+            0x1051 => HaruErrorKind::PageInvalidGmode,
+            0x1052 => HaruErrorKind::PageInvalidIndex,
+            0x1053 => HaruErrorKind::PageInvalidRotateValue,
+            0x1054 => HaruErrorKind::PageInvalidSize,
+            0x1055 => HaruErrorKind::PageInvalidXobject,
+            0x1056 => HaruErrorKind::PageOutOfRange,
+            0x1057 => HaruErrorKind::RealOutOfRange,
+            0x1058 => HaruErrorKind::StreamEof,
+            0x1059 => HaruErrorKind::StreamReadlnContinue,
+            0x105B => HaruErrorKind::StringOutOfRange,
+            0x105C => HaruErrorKind::ThisFuncWasSkipped,
+            0x105D => HaruErrorKind::TtfCannotEmbeddingFont,
+            0x105E => HaruErrorKind::TtfInvalidCmap,
+            0x105F => HaruErrorKind::TtfInvalidFomat,
+            0x1060 => HaruErrorKind::TtfMissingTable,
+            0x1061 => HaruErrorKind::UnsupportedFontType,
+            0x1062 => HaruErrorKind::UnsupportedFunc,
+            0x1063 => HaruErrorKind::UnsupportedJpegFormat,
+            0x1064 => HaruErrorKind::UnsupportedType1Font,
+            0x1065 => HaruErrorKind::XrefCountErr,
+            0x1066 => HaruErrorKind::ZlibError,
+            0x1067 => HaruErrorKind::InvalidPageIndex,
+            0x1068 => HaruErrorKind::InvalidUri,
+            0x1069 => HaruErrorKind::PageLayoutOutOfRange,
+            0x1070 => HaruErrorKind::PageModeOutOfRange,
+            0x1071 => HaruErrorKind::PageNumStyleOutOfRange,
+            0x1072 => HaruErrorKind::AnnotInvalidIcon,
+            0x1073 => HaruErrorKind::AnnotInvalidBorderStyle,
+            0x1074 => HaruErrorKind::PageInvalidDirection,
+            0x1075 => HaruErrorKind::InvalidFont,
+            0x1076 => HaruErrorKind::PageInsufficientSpace,
+            0x1077 => HaruErrorKind::PageInvalidDisplayTime,
+            0x1078 => HaruErrorKind::PageInvalidTransitionTime,
+            0x1079 => HaruErrorKind::InvalidPageSlideshowType,
+            0x1080 => HaruErrorKind::ExtGstateOutOfRange,
+            0x1081 => HaruErrorKind::InvalidExtGstate,
+            0x1082 => HaruErrorKind::ExtGstateReadOnly,
+            0x1083 => HaruErrorKind::InvalidU3dData,
+            0x1084 => HaruErrorKind::NameCannotGetNames,
+            0x1085 => HaruErrorKind::InvalidIccComponentNum,
+            0x1086 => HaruErrorKind::PageInvalidBoundary,
+            0x1088 => HaruErrorKind::PageInvalidShadingType,
+            _ => HaruErrorKind::UnknownError,
+        }
+    }
+}
+
+impl std::fmt::Display for HaruErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// The error type for libharu.
+///
+/// Besides the error code, it carries the detail code libharu reports along
+/// with it and the name of the API function that failed. Errors of the
+/// writer passed to `PdfDocument::save_to_writer()` are kept as the source.
+///
+#[derive(Debug, Clone)]
+pub struct HaruError {
+    error_no: u64,
+    detail_no: u64,
+    api: Option<&'static str>,
+    io_error: Option<Arc<std::io::Error>>,
+}
+
+impl HaruError {
+    /// Create an error from libharu's error and detail codes.
+    ///
+    pub fn new(error_no: u64, detail_no: u64) -> Self {
+        Self {
+            error_no,
+            detail_no,
+            api: None,
+            io_error: None,
+        }
+    }
+
+    /// Wraps an I/O error as `FileIoError`, with the OS error code, if any,
+    /// as the detail code.
+    ///
+    pub(crate) fn from_io_error(error: std::io::Error) -> Self {
+        let detail_no = error.raw_os_error().unwrap_or(0) as u64;
+        Self {
+            io_error: Some(Arc::new(error)),
+            ..Self::new(hb::HPDF_FILE_IO_ERROR as u64, detail_no)
+        }
+    }
+
+    /// Builds the error for a failed call to `api` from the status it returned
+    /// and the error record of the object it was called on. A status of 0 is
+    /// used for functions that signal failure by returning NULL.
+    ///
+    pub(crate) fn from_error_rec(
+        api: &'static str,
+        status: hb::HPDF_STATUS,
+        error: hb::HPDF_Error,
+    ) -> Self {
+        let mut err = Self::new(status, 0).with_api(api);
+        if !error.is_null() {
+            let rec = unsafe { *error };
+            if rec.error_no != 0 && (status == 0 || status == rec.error_no) {
+                err.error_no = rec.error_no;
+                err.detail_no = rec.detail_no;
+            }
+        }
+        err
+    }
+
+    /// Set the name of the API function that failed.
+    ///
+    pub fn with_api(mut self, api: &'static str) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Returns the kind of the error.
+    ///
+    pub fn kind(&self) -> HaruErrorKind {
+        HaruErrorKind::from(self.error_no)
+    }
+
+    /// Returns the raw libharu error code.
+    ///
+    pub fn error_no(&self) -> u64 {
+        self.error_no
+    }
+
+    /// Returns the detail code. Its meaning depends on the error, e.g. the
+    /// errno of a failed file operation or the zlib/libpng error code.
+    ///
+    pub fn detail_no(&self) -> u64 {
+        self.detail_no
+    }
+
+    /// Returns the name of the API function that failed, if known.
+    ///
+    pub fn api(&self) -> Option<&'static str> {
+        self.api
+    }
+
+    /// Returns the I/O error which caused this error, if any.
+    ///
+    pub fn io_error(&self) -> Option<&std::io::Error> {
+        self.io_error.as_deref()
+    }
+}
+
+impl PartialEq for HaruError {
+    fn eq(&self, other: &Self) -> bool {
+        self.error_no == other.error_no
+            && self.detail_no == other.detail_no
+            && self.api == other.api
+            && self.io_error.as_ref().map(|e| e.kind()) == other.io_error.as_ref().map(|e| e.kind())
+    }
+}
+
+impl Eq for HaruError {}
+
+impl From<u64> for HaruError {
+    fn from(code: u64) -> Self {
+        Self::new(code, 0)
+    }
+}
+
+impl std::fmt::Display for HaruError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(api) = self.api {
+            write!(f, "{} failed: ", api)?;
+        }
+        write!(
+            f,
+            "{} (error_no: 0x{:04X}, detail_no: {})",
+            self.kind(),
+            self.error_no,
+            self.detail_no
+        )?;
+        if let Some(io_error) = &self.io_error {
+            write!(f, ": {}", io_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for HaruError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.io_error
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Dash pattern presets for stroking lines, see `PdfPage::set_dash_pattern()`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashPattern {
+    /// A solid line.
+    Solid,
+    /// Dots of 1 unit, separated by 2 units.
+    Dotted,
+    /// Dashes of 6 units, separated by 3 units.
+    Dashed,
+    /// Alternating dashes and dots, separated by 3 units.
+    DashDot,
+}
+
+impl DashPattern {
+    /// Returns the lengths of the alternating dashes and gaps.
+    ///
+    pub fn pattern(&self) -> &'static [f32] {
+        match self {
+            DashPattern::Solid => &[],
+            DashPattern::Dotted => &[1.0, 2.0],
+            DashPattern::Dashed => &[6.0, 3.0],
+            DashPattern::DashDot => &[6.0, 3.0, 1.0, 3.0],
         }
     }
 }
 
 /// The LineCap
 ///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LineCap {
     /// The line is squared off at the endpoint of the path.
     ButtEnd,
@@ -405,6 +1126,7 @@ impl From<hb::HPDF_LineCap> for LineCap {
 
 /// The LineJoin
 ///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LineJoin {
     /// The outer edges of the strokes for the two segments are extended until they meet at an angle, as in a picture frame.
     MiterJoin,
@@ -437,6 +1159,7 @@ impl From<hb::HPDF_LineJoin> for LineJoin {
 
 /// The RenderingMode
 ///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RenderingMode {
     /// Fill text.
     Fill,
@@ -487,6 +1210,141 @@ impl From<hb::HPDF_TextRenderingMode> for RenderingMode {
     }
 }
 
+/// The graphics mode of a page. It tells which operators are allowed next.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GMode {
+    /// Outside of paths and text objects.
+    PageDescription,
+    /// Between starting a path and painting it.
+    PathObject,
+    /// Between begin_text() and end_text().
+    TextObject,
+    /// After clip() or eo_clip(), before the path is painted.
+    ClippingPath,
+    Shading,
+    InlineImage,
+    ExternalObject,
+}
+
+impl From<hb::HPDF_UINT16> for GMode {
+    fn from(mode: hb::HPDF_UINT16) -> Self {
+        match mode as u32 {
+            hb::HPDF_GMODE_PATH_OBJECT => GMode::PathObject,
+            hb::HPDF_GMODE_TEXT_OBJECT => GMode::TextObject,
+            hb::HPDF_GMODE_CLIPPING_PATH => GMode::ClippingPath,
+            hb::HPDF_GMODE_SHADING => GMode::Shading,
+            hb::HPDF_GMODE_INLINE_IMAGE => GMode::InlineImage,
+            hb::HPDF_GMODE_EXTERNAL_OBJECT => GMode::ExternalObject,
+            _ => GMode::PageDescription,
+        }
+    }
+}
+
+/// A position on the page.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl From<hb::HPDF_Point> for Point {
+    fn from(point: hb::HPDF_Point) -> Self {
+        Point {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+/// A transformation matrix `[a b c d x y]`, as used for the current
+/// transformation matrix and the text matrix.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransMatrix {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl From<hb::HPDF_TransMatrix> for TransMatrix {
+    fn from(matrix: hb::HPDF_TransMatrix) -> Self {
+        TransMatrix {
+            a: matrix.a,
+            b: matrix.b,
+            c: matrix.c,
+            d: matrix.d,
+            x: matrix.x,
+            y: matrix.y,
+        }
+    }
+}
+
+/// An RGB color, with components between 0.0 and 1.0.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RgbColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl From<hb::HPDF_RGBColor> for RgbColor {
+    fn from(color: hb::HPDF_RGBColor) -> Self {
+        RgbColor {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        }
+    }
+}
+
+/// A CMYK color, with components between 0.0 and 1.0.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CmykColor {
+    pub c: f32,
+    pub m: f32,
+    pub y: f32,
+    pub k: f32,
+}
+
+impl From<hb::HPDF_CMYKColor> for CmykColor {
+    fn from(color: hb::HPDF_CMYKColor) -> Self {
+        CmykColor {
+            c: color.c,
+            m: color.m,
+            y: color.y,
+            k: color.k,
+        }
+    }
+}
+
+/// The dash pattern used for stroking lines. An empty pattern means solid
+/// lines.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashMode {
+    /// The lengths of the alternating dashes and gaps.
+    pub pattern: Vec<f32>,
+    /// The offset into the pattern at which lines start.
+    pub phase: f32,
+}
+
+impl From<hb::HPDF_DashMode> for DashMode {
+    fn from(mode: hb::HPDF_DashMode) -> Self {
+        let len = (mode.num_ptn as usize).min(mode.ptn.len());
+        DashMode {
+            pattern: mode.ptn[..len].to_vec(),
+            phase: mode.phase,
+        }
+    }
+}
+
 /// Alignment of the text.
 ///
 pub enum TextAlign {
@@ -551,7 +1409,7 @@ pub enum ImageAlign {
 
 /// Object Rect Type
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rect {
     pub x: f32,
     pub y: f32,
@@ -568,4 +1426,13 @@ impl Rect {
             height,
         }
     }
+
+    /// Returns true if `other` lies completely inside this rect.
+    ///
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
 }
@@ -0,0 +1,53 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! The ICC profile struct and related functions.
+//!
+//! ## Implementation status:
+//!
+//! - [x] HPDF_LoadIccProfileFromFile()
+//! - [x] HPDF_ICC_LoadIccFromMem()
+//!
+
+use crate::{document::PdfDocument, haru_bindings as hb};
+
+/// An ICC color profile loaded into the document. It can be used as the
+/// output intent of the document and as the color space of images.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct PdfIccProfile<'doc> {
+    /// The ICC stream dictionary.
+    pub(crate) icc: hb::HPDF_OutputIntent,
+    /// The number of color components of the profile (1, 3 or 4).
+    pub(crate) num_components: u32,
+    /// The document owning the profile.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+impl PdfIccProfile<'_> {
+    /// get_num_components() returns the number of color components of the
+    /// profile: 1 for gray, 3 for RGB and 4 for CMYK profiles.
+    ///
+    pub fn get_num_components(&self) -> u32 {
+        self.num_components
+    }
+}
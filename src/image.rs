@@ -23,17 +23,28 @@
 //! The image struct and related functions.
 //!
 
-use crate::{haru_bindings as hb, prelude::HaruError};
+use std::{ffi::CString, ops::RangeInclusive};
 
-/// The image object.
+use crate::{
+    document::PdfDocument, haru_bindings as hb, icc_profile::PdfIccProfile, prelude::HaruError,
+};
+
+/// The image object. It borrows the document it was loaded into.
 ///
 #[derive(Debug)]
-pub struct PdfImage {
+pub struct PdfImage<'doc> {
     /// The reference to the haru image.
-    pub image_ref: hb::HPDF_Image,
+    pub(crate) image_ref: hb::HPDF_Image,
+    /// The document owning the image.
+    pub(crate) doc: &'doc PdfDocument,
 }
 
-impl PdfImage {
+impl PdfImage<'_> {
+    /// Builds the error for a failed call from the image's error record.
+    pub(crate) fn error(&self, api: &'static str, status: hb::HPDF_STATUS) -> HaruError {
+        HaruError::from_error_rec(api, status, unsafe { (*self.image_ref).error })
+    }
+
     /// get_width() returns the width of the image.
     ///
     /// Api: HPDF_Image_GetWidth
@@ -41,7 +52,7 @@ impl PdfImage {
     pub fn get_width(&self) -> Result<u32, HaruError> {
         let result = unsafe { hb::HPDF_Image_GetWidth(self.image_ref) };
         match result {
-            0 => Err(HaruError::from(0)),
+            0 => Err(self.error("HPDF_Image_GetWidth", 0)),
             _ => Ok(result),
         }
     }
@@ -53,8 +64,272 @@ impl PdfImage {
     pub fn get_height(&self) -> Result<u32, HaruError> {
         let result = unsafe { hb::HPDF_Image_GetHeight(self.image_ref) };
         match result {
-            0 => Err(HaruError::from(0)),
+            0 => Err(self.error("HPDF_Image_GetHeight", 0)),
             _ => Ok(result),
         }
     }
+
+    /// set_color_space() makes the image use an ICC based color space. The
+    /// number of color components of the profile has to match the color
+    /// space the image was loaded with.
+    ///
+    /// API: HPDF_Image_SetColorSpace
+    ///
+    pub fn set_color_space(&self, profile: &PdfIccProfile<'_>) -> Result<&Self, HaruError> {
+        self.doc.ensure_same(
+            profile.doc,
+            "HPDF_Image_SetColorSpace",
+            hb::HPDF_INVALID_OBJECT,
+        )?;
+        if self.get_num_components() != Some(profile.num_components) {
+            return Err(HaruError::from(hb::HPDF_INVALID_COLOR_SPACE as u64)
+                .with_api("HPDF_Image_SetColorSpace"));
+        }
+
+        let icc_based = CString::new("ICCBased").unwrap();
+        let result = unsafe {
+            let array = hb::HPDF_Array_New((*self.image_ref).mmgr);
+            if array.is_null() {
+                return Err(self.error("HPDF_Array_New", 0));
+            }
+            let mut result = hb::HPDF_Array_AddName(array, icc_based.as_ptr());
+            result += hb::HPDF_Array_Add(array, profile.icc as *mut _);
+            match result {
+                0 => hb::HPDF_Image_SetColorSpace(self.image_ref, array),
+                _ => result,
+            }
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Image_SetColorSpace", result)),
+        }
+    }
+
+    /// set_color_mask() makes all pixels whose red, green and blue values
+    /// are within the given ranges transparent. The image has to be an 8 bit
+    /// DeviceRGB image.
+    ///
+    /// API: HPDF_Image_SetColorMask
+    ///
+    pub fn set_color_mask(
+        &self,
+        red: RangeInclusive<u8>,
+        green: RangeInclusive<u8>,
+        blue: RangeInclusive<u8>,
+    ) -> Result<&Self, HaruError> {
+        const API: &str = "HPDF_Image_SetColorMask";
+        if red.is_empty() || green.is_empty() || blue.is_empty() {
+            return Err(HaruError::from(hb::HPDF_INVALID_PARAMETER as u64).with_api(API));
+        }
+        if self.get_bits_per_component() != 8 {
+            return Err(HaruError::from(hb::HPDF_INVALID_BIT_PER_COMPONENT as u64).with_api(API));
+        }
+        if self.get_color_space_name() != Some(b"DeviceRGB") {
+            return Err(HaruError::from(hb::HPDF_INVALID_COLOR_SPACE as u64).with_api(API));
+        }
+
+        let result = unsafe {
+            hb::HPDF_Image_SetColorMask(
+                self.image_ref,
+                *red.start() as hb::HPDF_UINT,
+                *red.end() as hb::HPDF_UINT,
+                *green.start() as hb::HPDF_UINT,
+                *green.end() as hb::HPDF_UINT,
+                *blue.start() as hb::HPDF_UINT,
+                *blue.end() as hb::HPDF_UINT,
+            )
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error(API, result)),
+        }
+    }
+
+    /// set_mask_image() uses a 1 bit DeviceGray image as stencil mask. With
+    /// the default decode array `[0 1]`, the image is masked out where the
+    /// mask is 1 and painted where it is 0.
+    ///
+    /// The mask image loses its color space and becomes a stencil mask. It
+    /// can still mask other images, but can't be drawn on its own or be
+    /// masked itself afterwards.
+    ///
+    /// API: HPDF_Image_SetMaskImage
+    ///
+    pub fn set_mask_image(&self, mask: &PdfImage<'_>) -> Result<&Self, HaruError> {
+        const API: &str = "HPDF_Image_SetMaskImage";
+        self.doc
+            .ensure_same(mask.doc, API, hb::HPDF_INVALID_IMAGE)?;
+        if mask.image_ref == self.image_ref || self.is_stencil_mask() {
+            return Err(HaruError::from(hb::HPDF_INVALID_IMAGE as u64).with_api(API));
+        }
+        let reused = mask.is_stencil_mask();
+        if !reused && mask.get_bits_per_component() != 1 {
+            return Err(HaruError::from(hb::HPDF_INVALID_BIT_PER_COMPONENT as u64).with_api(API));
+        }
+        if !reused && mask.get_color_space_name() != Some(b"DeviceGray") {
+            return Err(HaruError::from(hb::HPDF_INVALID_COLOR_SPACE as u64).with_api(API));
+        }
+
+        let result = unsafe { hb::HPDF_Image_SetMaskImage(self.image_ref, mask.image_ref) };
+        if result != 0 {
+            return Err(self.error(API, result));
+        }
+        if reused {
+            return Ok(self);
+        }
+
+        // Image masks must not have a color space.
+        let color_space_key = CString::new("ColorSpace").unwrap();
+        let result =
+            unsafe { hb::HPDF_Dict_RemoveElement(mask.image_ref, color_space_key.as_ptr()) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Dict_RemoveElement", result)),
+        }
+    }
+
+    /// add_smask() uses a DeviceGray image as soft mask. The gray values
+    /// give the opacity of the image, from 0 (transparent) to the maximum
+    /// value (opaque). An image can only have one soft mask. PDF/A-1 doesn't
+    /// allow soft masks.
+    ///
+    /// API: HPDF_Image_AddSMask
+    ///
+    pub fn add_smask(&self, smask: &PdfImage<'_>) -> Result<&Self, HaruError> {
+        const API: &str = "HPDF_Image_AddSMask";
+        self.doc.ensure_not_pdfa(API)?;
+        self.doc
+            .ensure_same(smask.doc, API, hb::HPDF_INVALID_IMAGE)?;
+        if smask.image_ref == self.image_ref {
+            return Err(HaruError::from(hb::HPDF_INVALID_IMAGE as u64).with_api(API));
+        }
+        if smask.get_color_space_name() != Some(b"DeviceGray") {
+            return Err(HaruError::from(hb::HPDF_INVALID_COLOR_SPACE as u64).with_api(API));
+        }
+
+        // libharu looks for an existing soft mask with the wrong type.
+        let smask_key = CString::new("SMask").unwrap();
+        let existing = unsafe {
+            hb::HPDF_Dict_GetItem(
+                self.image_ref,
+                smask_key.as_ptr(),
+                hb::HPDF_OCLASS_DICT as hb::HPDF_UINT16,
+            )
+        };
+        if !existing.is_null() {
+            return Err(HaruError::from(hb::HPDF_INVALID_OPERATION as u64).with_api(API));
+        }
+
+        let result = unsafe { hb::HPDF_Image_AddSMask(self.image_ref, smask.image_ref) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error(API, result)),
+        }
+    }
+
+    /// Returns the bits per component of the image, or 0 if unknown.
+    fn get_bits_per_component(&self) -> u32 {
+        unsafe { hb::HPDF_Image_GetBitsPerComponent(self.image_ref) }
+    }
+
+    /// Returns the name of the image's color space. For ICC based color
+    /// spaces this is "ICCBased".
+    fn get_color_space_name(&self) -> Option<&[u8]> {
+        let name = unsafe { hb::HPDF_Image_GetColorSpace(self.image_ref) };
+        match name.is_null() {
+            true => None,
+            false => Some(unsafe { std::ffi::CStr::from_ptr(name) }.to_bytes()),
+        }
+    }
+
+    /// Returns true if the image was turned into a stencil mask by
+    /// set_mask_image().
+    fn is_stencil_mask(&self) -> bool {
+        let image_mask_key = CString::new("ImageMask").unwrap();
+        unsafe {
+            let value = hb::HPDF_Dict_GetItem(
+                self.image_ref,
+                image_mask_key.as_ptr(),
+                hb::HPDF_OCLASS_BOOLEAN as hb::HPDF_UINT16,
+            ) as hb::HPDF_Boolean;
+            !value.is_null() && (*value).value != hb::HPDF_FALSE as hb::HPDF_BOOL
+        }
+    }
+
+    /// Returns the number of color components of the image's device color
+    /// space, or None for other color spaces.
+    fn get_num_components(&self) -> Option<u32> {
+        match self.get_color_space_name()? {
+            b"DeviceGray" => Some(1),
+            b"DeviceRGB" => Some(3),
+            b"DeviceCMYK" => Some(4),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haru_types::{ColorSpace, HaruErrorKind, Rect};
+
+    #[test]
+    fn test_image_masks() {
+        let doc = PdfDocument::new();
+        let rgb = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceRGB, 8, &[0xff; 12])
+            .unwrap();
+        let gray = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 8, &[0x80; 4])
+            .unwrap();
+        let bitmap = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 1, &[0x80; 2])
+            .unwrap();
+
+        rgb.set_color_mask(250..=255, 250..=255, 250..=255).unwrap();
+        let err = gray.set_color_mask(0..=0, 0..=0, 0..=0).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidColorSpace);
+        let err = rgb
+            .set_color_mask(RangeInclusive::new(10, 0), 0..=0, 0..=0)
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidParameter);
+
+        rgb.add_smask(&gray).unwrap();
+        let err = rgb.add_smask(&gray).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidOperation);
+        let err = gray.add_smask(&rgb).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidColorSpace);
+
+        let err = gray.set_mask_image(&gray).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidImage);
+        let err = rgb.set_mask_image(&gray).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidBitPerComponent);
+        gray.set_mask_image(&bitmap).unwrap();
+
+        // The stencil mask can mask more images, but can't be masked itself.
+        let second = doc
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 8, &[0x40; 4])
+            .unwrap();
+        second.set_mask_image(&bitmap).unwrap();
+        let err = bitmap.set_mask_image(&second).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::InvalidImage);
+
+        let other = PdfDocument::new();
+        let foreign = other
+            .load_raw_image(2, 2, ColorSpace::DeviceGray, 8, &[0x80; 4])
+            .unwrap();
+        assert!(rgb.add_smask(&foreign).is_err());
+
+        let page = doc.add_page().unwrap();
+        page.draw_image(&rgb, &Rect::new(10.0, 10.0, 20.0, 20.0))
+            .unwrap();
+        page.draw_image(&gray, &Rect::new(30.0, 10.0, 40.0, 20.0))
+            .unwrap();
+        page.draw_image(&second, &Rect::new(50.0, 10.0, 60.0, 20.0))
+            .unwrap();
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("/Mask [ 250 255 250 255 250 255 ]"));
+        assert_eq!(pdf.matches("/ImageMask true").count(), 1);
+    }
 }
@@ -29,20 +29,32 @@
 
 mod haru_bindings;
 
+pub mod destination;
 pub mod document;
+pub mod embedded_file;
+pub mod ext_gstate;
 pub mod font;
 pub mod haru_types;
+pub mod icc_profile;
 pub mod image;
+pub mod outline;
 pub mod page;
+pub mod typestate;
 
 /// The prelude module.
 ///
 pub mod prelude {
+    pub use crate::destination::*;
     pub use crate::document::*;
+    pub use crate::embedded_file::*;
+    pub use crate::ext_gstate::*;
     pub use crate::font::*;
     pub use crate::haru_types::*;
+    pub use crate::icc_profile::*;
     pub use crate::image::*;
+    pub use crate::outline::*;
     pub use crate::page::*;
+    pub use crate::typestate::*;
 }
 
 /// The error callback function type
@@ -0,0 +1,97 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! The outline (bookmarks) struct and related functions.
+//!
+//! ## Implementation status:
+//!
+//! - [x] HPDF_CreateOutline()
+//! - [x] HPDF_Outline_SetOpened()
+//! - [x] HPDF_Outline_SetDestination()
+//!
+
+use crate::{
+    destination::PdfDestination, document::PdfDocument, haru_bindings as hb, haru_types::HaruError,
+};
+
+/// An entry of the document outline. Outlines can be nested by adding children.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct PdfOutline<'doc> {
+    /// The reference to the haru outline.
+    pub(crate) outline: hb::HPDF_Outline,
+    /// The document owning the outline.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+impl<'doc> PdfOutline<'doc> {
+    /// Builds the error for a failed call from the outline's error record.
+    fn error(&self, api: &'static str, status: hb::HPDF_STATUS) -> HaruError {
+        HaruError::from_error_rec(api, status, unsafe { (*self.outline).error })
+    }
+
+    /// add_child() creates a new outline entry below this one.
+    /// See PdfDocument::create_outline() for the meaning of encoding_name.
+    ///
+    /// API: HPDF_CreateOutline
+    ///
+    pub fn add_child(
+        &self,
+        title: &str,
+        encoding_name: Option<&str>,
+    ) -> Result<PdfOutline<'doc>, HaruError> {
+        self.doc
+            .create_outline_below(self.outline, title, encoding_name)
+    }
+
+    /// set_opened() sets whether this outline is displayed with its
+    /// children expanded.
+    ///
+    /// API: HPDF_Outline_SetOpened
+    ///
+    pub fn set_opened(&self, opened: bool) -> Result<&Self, HaruError> {
+        let opened = if opened { 1 } else { 0 };
+        let result = unsafe { hb::HPDF_Outline_SetOpened(self.outline, opened) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Outline_SetOpened", result)),
+        }
+    }
+
+    /// set_destination() sets where the viewer jumps to when the
+    /// outline is clicked.
+    ///
+    /// API: HPDF_Outline_SetDestination
+    ///
+    pub fn set_destination(&self, dst: &PdfDestination<'_>) -> Result<&Self, HaruError> {
+        self.doc.ensure_same(
+            dst.doc,
+            "HPDF_Outline_SetDestination",
+            hb::HPDF_INVALID_DESTINATION,
+        )?;
+        let result = unsafe { hb::HPDF_Outline_SetDestination(self.outline, dst.dst) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Outline_SetDestination", result)),
+        }
+    }
+}
@@ -58,8 +58,9 @@
 //! - [x] HPDF_Page_SetCharSpace()
 //! - [x] HPDF_Page_SetCMYKFill()
 //! - [x] HPDF_Page_SetCMYKStroke()
-//! - [ ] HPDF_Page_SetDash()
-//! - [ ] HPDF_Page_SetExtGState()
+//! - [x] HPDF_Page_SetDash()
+//! - [x] HPDF_Page_SetExtGState()
+//! - [x] HPDF_Page_SetFlat()
 //! - [x] HPDF_Page_SetFontAndSize()
 //! - [x] HPDF_Page_SetGrayFill()
 //! - [x] HPDF_Page_SetGrayStroke()
@@ -84,27 +85,230 @@
 
 use std::ptr;
 
+use haru_types::BoundaryKind;
+use haru_types::CmykColor;
+use haru_types::ColorSpace;
+use haru_types::DashMode;
+use haru_types::DashPattern;
+use haru_types::Destination;
+use haru_types::GMode;
 use haru_types::HaruError;
 use haru_types::LineCap;
+use haru_types::LineJoin;
+use haru_types::PageDirection;
+use haru_types::PageSize;
+use haru_types::Point;
 use haru_types::Rect;
 use haru_types::RenderingMode;
+use haru_types::RgbColor;
 use haru_types::TextAlign;
+use haru_types::TransMatrix;
 
+use crate::destination::PdfDestination;
+use crate::document::PdfDocument;
+use crate::ext_gstate::PdfExtGState;
 use crate::font::PdfFont;
 use crate::haru_bindings as hb;
 use crate::haru_types;
 use crate::haru_types::ImageFit;
 use crate::prelude::PdfImage;
 
-/// The PDF Page API.
+/// The PDF Page API. A page borrows the document it belongs to, so it
+/// can't outlive it:
+///
+/// ```compile_fail
+/// use libharu_ng::prelude::*;
+///
+/// let page = {
+///     let doc = PdfDocument::new();
+///     doc.add_page().unwrap()
+/// };
+/// page.get_width();
+/// ```
 ///
 #[derive(Debug, Copy, Clone)]
-pub struct PdfPage {
+pub struct PdfPage<'doc> {
     /// The reference to the haru page.
-    pub page: hb::HPDF_Page,
+    pub(crate) page: hb::HPDF_Page,
+    /// The document owning the page.
+    pub(crate) doc: &'doc PdfDocument,
+}
+
+/// Iterator over the pages of a document, returned by
+/// [`PdfDocument::pages()`].
+///
+#[derive(Debug)]
+pub struct Pages<'doc> {
+    pub(crate) doc: &'doc PdfDocument,
+    pub(crate) index: u32,
+}
+
+impl<'doc> Iterator for Pages<'doc> {
+    type Item = PdfPage<'doc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.doc.page_count() {
+            return None;
+        }
+        let page = self.doc.page(self.index).ok()?;
+        self.index += 1;
+        Some(page)
+    }
 }
 
-impl PdfPage {
+/// The graphics state of a page at one point in time, returned by
+/// [`PdfPage::graphics_state()`].
+///
+#[derive(Debug, Clone)]
+pub struct GraphicsStateSnapshot<'doc> {
+    pub gmode: GMode,
+    /// The current point, inside of paths only.
+    pub current_pos: Option<Point>,
+    /// The current text position, inside of text objects only.
+    pub current_text_pos: Option<Point>,
+    pub font: Option<PdfFont<'doc>>,
+    pub font_size: f32,
+    pub trans_matrix: TransMatrix,
+    pub text_matrix: TransMatrix,
+    pub line_width: f32,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    pub miter_limit: f32,
+    pub dash: DashMode,
+    pub flat: f32,
+    pub char_space: f32,
+    pub word_space: f32,
+    pub horizontal_scalling: f32,
+    pub text_leading: f32,
+    pub text_rendering_mode: RenderingMode,
+    pub text_rise: f32,
+    pub rgb_fill: RgbColor,
+    pub rgb_stroke: RgbColor,
+    pub cmyk_fill: CmykColor,
+    pub cmyk_stroke: CmykColor,
+    pub gray_fill: f32,
+    pub gray_stroke: f32,
+    pub filling_color_space: Option<ColorSpace>,
+    pub stroking_color_space: Option<ColorSpace>,
+    /// The nesting depth of the graphics state, 1 without g_save().
+    pub gstate_depth: u32,
+}
+
+/// Saved graphics state of a page, returned by [`PdfPage::saved_state()`].
+/// The graphics state is restored when the guard is dropped, so early
+/// returns can't leave `q` and `Q` operators unbalanced. The guard
+/// dereferences to the page for drawing.
+///
+#[derive(Debug)]
+#[must_use = "the graphics state is restored when the guard is dropped"]
+pub struct SavedState<'doc> {
+    page: PdfPage<'doc>,
+    /// The graphics state depth right after saving.
+    depth: u32,
+}
+
+impl<'doc> SavedState<'doc> {
+    /// restore() restores the graphics state now and reports errors, which
+    /// dropping the guard can't.
+    ///
+    /// API: HPDF_Page_GRestore
+    ///
+    pub fn restore(self) -> Result<PdfPage<'doc>, HaruError> {
+        let result = self.close();
+        let page = self.page;
+        std::mem::forget(self);
+        result.map(|_| page)
+    }
+
+    /// Ends an unfinished path or text object and restores the graphics
+    /// states saved since the guard was created.
+    fn close(&self) -> Result<(), HaruError> {
+        self.page.doc.with_error_put_aside(|| {
+            match self.page.get_gmode() {
+                GMode::TextObject => {
+                    self.page.end_text()?;
+                }
+                GMode::PathObject | GMode::ClippingPath => {
+                    self.page.end_path()?;
+                }
+                _ => {}
+            }
+            while self.page.get_gstate_depth() >= self.depth {
+                self.page.g_restore()?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<'doc> std::ops::Deref for SavedState<'doc> {
+    type Target = PdfPage<'doc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
+}
+
+impl Drop for SavedState<'_> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Text object of a page, returned by [`PdfPage::text_block()`]. The text
+/// object is ended when the guard is dropped. The guard dereferences to the
+/// page for showing text.
+///
+#[derive(Debug)]
+#[must_use = "the text object is ended when the guard is dropped"]
+pub struct TextBlock<'doc> {
+    page: PdfPage<'doc>,
+}
+
+impl<'doc> TextBlock<'doc> {
+    /// end() ends the text object now and reports errors, which dropping the
+    /// guard can't.
+    ///
+    /// API: HPDF_Page_EndText
+    ///
+    pub fn end(self) -> Result<PdfPage<'doc>, HaruError> {
+        let result = self.close();
+        let page = self.page;
+        std::mem::forget(self);
+        result.map(|_| page)
+    }
+
+    /// Ends the text object, unless it was ended already.
+    fn close(&self) -> Result<(), HaruError> {
+        self.page.doc.with_error_put_aside(|| {
+            if self.page.get_gmode() == GMode::TextObject {
+                self.page.end_text()?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<'doc> std::ops::Deref for TextBlock<'doc> {
+    type Target = PdfPage<'doc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
+}
+
+impl Drop for TextBlock<'_> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+impl<'doc> PdfPage<'doc> {
+    /// Builds the error for a failed call from the page's error record.
+    pub(crate) fn error(&self, api: &'static str, status: hb::HPDF_STATUS) -> HaruError {
+        HaruError::from_error_rec(api, status, unsafe { (*self.page).error })
+    }
+
     /// arc() appends a circle arc to the current path. Angles
     /// are given in degrees, with 0 degrees being vertical, upward, from
     /// the (x,y) position.
@@ -122,7 +326,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Arc(self.page, x, y, radius, ang1, ang2) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Arc", result)),
         }
     }
 
@@ -134,7 +338,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_BeginText(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_BeginText", result)),
         }
     }
 
@@ -144,7 +348,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Circle(self.page, x, y, radius) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Circle", result)),
         }
     }
 
@@ -167,7 +371,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Clip(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Clip", result)),
         }
     }
 
@@ -181,7 +385,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ClosePath(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_ClosePath", result)),
         }
     }
 
@@ -193,7 +397,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ClosePathStroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_ClosePathStroke", result)),
         }
     }
 
@@ -206,7 +410,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ClosePathEofillStroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(0)),
+            _ => Err(self.error("HPDF_Page_ClosePathEofillStroke", result)),
         }
     }
 
@@ -219,7 +423,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ClosePathFillStroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(0)),
+            _ => Err(self.error("HPDF_Page_ClosePathFillStroke", result)),
         }
     }
 
@@ -239,7 +443,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Concat(self.page, a, b, c, d, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Concat", result)),
         }
     }
 
@@ -260,7 +464,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_CurveTo(self.page, x1, y1, x2, y2, x3, y3) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_CurveTo", result)),
         }
     }
 
@@ -273,7 +477,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_CurveTo2(self.page, x2, y2, x3, y3) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_CurveTo2", result)),
         }
     }
 
@@ -287,7 +491,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_CurveTo3(self.page, x1, y1, x3, y3) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_CurveTo3", result)),
         }
     }
 
@@ -299,7 +503,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Ellipse(self.page, x, y, xray, yray) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Ellipse", result)),
         }
     }
 
@@ -311,7 +515,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_EndPath(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_EndPath", result)),
         }
     }
 
@@ -323,7 +527,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_EndText(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_EndText", result)),
         }
     }
 
@@ -345,7 +549,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Eoclip(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Eoclip", result)),
         }
     }
 
@@ -357,7 +561,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Eofill(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Eofill", result)),
         }
     }
 
@@ -369,7 +573,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_EofillStroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_EofillStroke", result)),
         }
     }
 
@@ -381,7 +585,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Fill(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Fill", result)),
         }
     }
 
@@ -393,7 +597,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_FillStroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_FillStroke", result)),
         }
     }
 
@@ -405,7 +609,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_GRestore(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_GRestore", result)),
         }
     }
 
@@ -440,10 +644,33 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_GSave(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_GSave", result)),
         }
     }
 
+    /// saved_state() saves the graphics state and returns a guard, which
+    /// restores it when dropped.
+    ///
+    /// API: HPDF_Page_GSave, HPDF_Page_GRestore
+    ///
+    pub fn saved_state(&self) -> Result<SavedState<'doc>, HaruError> {
+        self.g_save()?;
+        Ok(SavedState {
+            page: *self,
+            depth: self.get_gstate_depth(),
+        })
+    }
+
+    /// text_block() begins a text object and returns a guard, which ends it
+    /// when dropped.
+    ///
+    /// API: HPDF_Page_BeginText, HPDF_Page_EndText
+    ///
+    pub fn text_block(&self) -> Result<TextBlock<'doc>, HaruError> {
+        self.begin_text()?;
+        Ok(TextBlock { page: *self })
+    }
+
     /// line_to() appends a path from the current point to the specified point.
     ///
     /// API: HPDF_Page_LineTo
@@ -452,7 +679,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_LineTo(self.page, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_LineTo", result)),
         }
     }
 
@@ -464,7 +691,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetCharSpace(self.page, value) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetCharSpace", result)),
         }
     }
 
@@ -476,7 +703,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetCMYKFill(self.page, c, m, y, k) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetCMYKFill", result)),
         }
     }
 
@@ -488,7 +715,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetCMYKStroke(self.page, c, m, y, k) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetCMYKStroke", result)),
         }
     }
 
@@ -496,11 +723,18 @@ impl PdfPage {
     ///
     /// API: HPDF_Page_SetFontAndSize
     ///
-    pub fn set_font_and_size(&self, font: PdfFont, size: f32) -> Result<&Self, HaruError> {
+    pub fn set_font_and_size(&self, font: PdfFont<'_>, size: f32) -> Result<&Self, HaruError> {
+        self.doc.ensure_same(
+            font.doc,
+            "HPDF_Page_SetFontAndSize",
+            hb::HPDF_PAGE_INVALID_FONT,
+        )?;
+        self.doc
+            .ensure_pdfa_font(&font, "HPDF_Page_SetFontAndSize")?;
         let result = unsafe { hb::HPDF_Page_SetFontAndSize(self.page, font.font_ref, size) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetFontAndSize", result)),
         }
     }
 
@@ -512,7 +746,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetGrayFill(self.page, gray) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetGrayFill", result)),
         }
     }
 
@@ -524,7 +758,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetGrayStroke(self.page, gray) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetGrayStroke", result)),
         }
     }
 
@@ -536,7 +770,65 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetHorizontalScalling(self.page, value) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetHorizontalScalling", result)),
+        }
+    }
+
+    /// set_dash() sets the dash pattern for stroking lines. `pattern` holds
+    /// up to 8 lengths of alternating dashes and gaps, each greater than 0
+    /// and at most 100. `phase` is the offset into the pattern at which the
+    /// line starts. An empty pattern draws solid lines.
+    ///
+    /// API: HPDF_Page_SetDash
+    ///
+    pub fn set_dash(&self, pattern: &[f32], phase: f32) -> Result<&Self, HaruError> {
+        let max = hb::HPDF_MAX_DASH_PATTERN as f32;
+        if pattern.len() > 8
+            || pattern
+                .iter()
+                .any(|&length| !(length > 0.0 && length <= max))
+            || !(phase >= 0.0 && phase.is_finite())
+            || (pattern.is_empty() && phase > 0.0)
+        {
+            return Err(
+                HaruError::from(hb::HPDF_PAGE_OUT_OF_RANGE as u64).with_api("HPDF_Page_SetDash")
+            );
+        }
+
+        let result = unsafe {
+            hb::HPDF_Page_SetDash(self.page, pattern.as_ptr(), pattern.len() as u32, phase)
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetDash", result)),
+        }
+    }
+
+    /// set_dash_pattern() sets one of the preset dash patterns.
+    ///
+    /// API: HPDF_Page_SetDash
+    ///
+    pub fn set_dash_pattern(&self, pattern: DashPattern) -> Result<&Self, HaruError> {
+        self.set_dash(pattern.pattern(), 0.0)
+    }
+
+    /// set_flat() sets the flatness tolerance, the maximum distance in device
+    /// pixels between curves and the line segments they are drawn with
+    /// (0 to 100).
+    ///
+    /// API: HPDF_Page_SetFlat
+    ///
+    pub fn set_flat(&self, flatness: f32) -> Result<&Self, HaruError> {
+        if !(0.0..=100.0).contains(&flatness) {
+            return Err(
+                HaruError::from(hb::HPDF_PAGE_OUT_OF_RANGE as u64).with_api("HPDF_Page_SetFlat")
+            );
+        }
+
+        let result = unsafe { hb::HPDF_Page_SetFlat(self.page, flatness) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetFlat", result)),
         }
     }
 
@@ -548,7 +840,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetLineCap(self.page, line_cap as u32) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetLineCap", result)),
         }
     }
 
@@ -560,7 +852,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetLineJoin(self.page, line_join as u32) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetLineJoin", result)),
         }
     }
 
@@ -572,7 +864,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetMiterLimit(self.page, miter_limit) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetMiterLimit", result)),
         }
     }
 
@@ -584,7 +876,26 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetLineWidth(self.page, line_width) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetLineWidth", result)),
+        }
+    }
+
+    /// set_ext_gstate() applies an extended graphics state to the page. It
+    /// stays in effect until the graphics state is restored. The extended
+    /// graphics state can't be changed afterwards.
+    ///
+    /// API: HPDF_Page_SetExtGState
+    ///
+    pub fn set_ext_gstate(&self, gstate: &PdfExtGState<'_>) -> Result<&Self, HaruError> {
+        self.doc.ensure_same(
+            gstate.doc,
+            "HPDF_Page_SetExtGState",
+            hb::HPDF_INVALID_EXT_GSTATE,
+        )?;
+        let result = unsafe { hb::HPDF_Page_SetExtGState(self.page, gstate.gstate) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetExtGState", result)),
         }
     }
 
@@ -598,7 +909,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_MoveTextPos(self.page, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_MoveTextPos", result)),
         }
     }
 
@@ -611,7 +922,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_MoveTextPos2(self.page, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_MoveTextPos2", result)),
         }
     }
 
@@ -624,7 +935,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_MoveTo(self.page, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_MoveTo", result)),
         }
     }
 
@@ -638,7 +949,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_MoveToNextLine(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_MoveToNextLine", result)),
         }
     }
 
@@ -650,7 +961,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Rectangle(self.page, x, y, width, height) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Rectangle", result)),
         }
     }
 
@@ -662,7 +973,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetWidth(self.page, width) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetWidth", result)),
         }
     }
 
@@ -674,10 +985,152 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetHeight(self.page, height) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetHeight", result)),
+        }
+    }
+
+    /// set_size() changes the size and direction of the page to a
+    /// predefined size.
+    ///
+    /// API: HPDF_Page_SetSize
+    ///
+    pub fn set_size(&self, size: PageSize, direction: PageDirection) -> Result<&Self, HaruError> {
+        let result = unsafe {
+            hb::HPDF_Page_SetSize(
+                self.page,
+                size.to_hpdf_page_size(),
+                direction.to_hpdf_page_direction(),
+            )
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetSize", result)),
+        }
+    }
+
+    /// set_rotate() sets the rotation angle of the page when it is displayed
+    /// or printed. The angle must be a multiple of 90 degrees.
+    ///
+    /// API: HPDF_Page_SetRotate
+    ///
+    pub fn set_rotate(&self, degrees: u16) -> Result<&Self, HaruError> {
+        let result = unsafe { hb::HPDF_Page_SetRotate(self.page, degrees) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetRotate", result)),
         }
     }
 
+    /// set_zoom() sets the magnification factor the viewer uses to display
+    /// the page. The zoom must be between 0.08 and 32.
+    ///
+    /// API: HPDF_Page_SetZoom
+    ///
+    pub fn set_zoom(&self, zoom: f32) -> Result<&Self, HaruError> {
+        let result = unsafe { hb::HPDF_Page_SetZoom(self.page, zoom) };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetZoom", result)),
+        }
+    }
+
+    /// set_boundary() sets one of the boundary boxes of the page. libharu
+    /// stores the box edges as whole points, so fractional values are
+    /// truncated.
+    ///
+    /// The boxes have to nest: the CropBox must lie inside the MediaBox, the
+    /// BleedBox, TrimBox and ArtBox inside the CropBox (or the MediaBox if no
+    /// CropBox is set), and the TrimBox inside the BleedBox. A box that would
+    /// break this is rejected with PageInvalidBoundary.
+    ///
+    /// API: HPDF_Page_SetBoundary
+    ///
+    pub fn set_boundary(&self, kind: BoundaryKind, rect: &Rect) -> Result<&Self, HaruError> {
+        let left = rect.x as i16 as f32;
+        let bottom = rect.y as i16 as f32;
+        let right = (rect.x + rect.width) as i16 as f32;
+        let top = (rect.y + rect.height) as i16 as f32;
+        let rect = Rect::new(left, bottom, right - left, top - bottom);
+
+        if rect.width <= 0.0 || rect.height <= 0.0 || !self.boundaries_nest(kind, &rect) {
+            return Err(HaruError::from(hb::HPDF_PAGE_INVALID_BOUNDARY as u64)
+                .with_api("HPDF_Page_SetBoundary"));
+        }
+
+        let result = unsafe {
+            hb::HPDF_Page_SetBoundary(
+                self.page,
+                kind.to_hpdf_page_boundary(),
+                left,
+                bottom,
+                right,
+                top,
+            )
+        };
+        match result {
+            0 => Ok(self),
+            _ => Err(self.error("HPDF_Page_SetBoundary", result)),
+        }
+    }
+
+    /// get_boundary() returns one of the boundary boxes of the page, or None
+    /// if the box is not set.
+    ///
+    pub fn get_boundary(&self, kind: BoundaryKind) -> Option<Rect> {
+        let key = std::ffi::CString::new(kind.key()).unwrap();
+        unsafe {
+            let array = hb::HPDF_Dict_GetItem(
+                self.page,
+                key.as_ptr(),
+                hb::HPDF_OCLASS_ARRAY as hb::HPDF_UINT16,
+            ) as hb::HPDF_Array;
+            if array.is_null() || hb::HPDF_Array_Items(array) != 4 {
+                return None;
+            }
+
+            let mut edges = [0.0; 4];
+            for (i, edge) in edges.iter_mut().enumerate() {
+                let real = hb::HPDF_Array_GetItem(
+                    array,
+                    i as u32,
+                    hb::HPDF_OCLASS_REAL as hb::HPDF_UINT16,
+                ) as hb::HPDF_Real;
+                if real.is_null() {
+                    return None;
+                }
+                *edge = (*real).value;
+            }
+
+            let [left, bottom, right, top] = edges;
+            Some(Rect::new(left, bottom, right - left, top - bottom))
+        }
+    }
+
+    /// Checks whether the boundary boxes still nest if `kind` is set to `rect`.
+    fn boundaries_nest(&self, kind: BoundaryKind, rect: &Rect) -> bool {
+        let get = |k: BoundaryKind| match k == kind {
+            true => Some(*rect),
+            false => self.get_boundary(k),
+        };
+        let media = get(BoundaryKind::MediaBox);
+        let crop = get(BoundaryKind::CropBox);
+        let bleed = get(BoundaryKind::BleedBox);
+        let trim = get(BoundaryKind::TrimBox);
+        let art = get(BoundaryKind::ArtBox);
+
+        let nests = |outer: Option<Rect>, inner: Option<Rect>| match (outer, inner) {
+            (Some(outer), Some(inner)) => outer.contains(&inner),
+            _ => true,
+        };
+        let visible = crop.or(media);
+
+        nests(media, crop)
+            && nests(visible, bleed)
+            && nests(visible, trim)
+            && nests(visible, art)
+            && nests(bleed, trim)
+    }
+
     /// set_text_rendering_mode() sets the text rendering mode.
     /// The initial value of text rendering mode is Fill.
     ///
@@ -687,7 +1140,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetTextRenderingMode(self.page, mode as u32) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetTextRenderingMode", result)),
         }
     }
 
@@ -701,7 +1154,7 @@ impl PdfPage {
         };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Concat", result)),
         }
     }
 
@@ -711,7 +1164,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Concat(self.page, 1.0, 0.0, 0.0, 1.0, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Concat", result)),
         }
     }
 
@@ -731,6 +1184,291 @@ impl PdfPage {
         unsafe { hb::HPDF_Page_GetHeight(self.page) }
     }
 
+    /// get_gmode() returns the graphics mode of the page.
+    ///
+    /// API: HPDF_Page_GetGMode
+    ///
+    pub fn get_gmode(&self) -> GMode {
+        GMode::from(unsafe { hb::HPDF_Page_GetGMode(self.page) })
+    }
+
+    /// get_current_pos() returns the current point of the path being
+    /// constructed, or None outside of a path.
+    ///
+    /// API: HPDF_Page_GetCurrentPos
+    ///
+    pub fn get_current_pos(&self) -> Option<Point> {
+        match self.get_gmode() {
+            GMode::PathObject => Some(Point::from(unsafe {
+                hb::HPDF_Page_GetCurrentPos(self.page)
+            })),
+            _ => None,
+        }
+    }
+
+    /// get_current_text_pos() returns the current text position, or None
+    /// outside of a text object.
+    ///
+    /// API: HPDF_Page_GetCurrentTextPos
+    ///
+    pub fn get_current_text_pos(&self) -> Option<Point> {
+        match self.get_gmode() {
+            GMode::TextObject => Some(Point::from(unsafe {
+                hb::HPDF_Page_GetCurrentTextPos(self.page)
+            })),
+            _ => None,
+        }
+    }
+
+    /// get_current_font() returns the current font, or None if no font is
+    /// set.
+    ///
+    /// API: HPDF_Page_GetCurrentFont
+    ///
+    pub fn get_current_font(&self) -> Option<PdfFont<'doc>> {
+        let font = unsafe { hb::HPDF_Page_GetCurrentFont(self.page) };
+        match font.is_null() {
+            true => None,
+            false => Some(PdfFont {
+                font_ref: font,
+                doc: self.doc,
+            }),
+        }
+    }
+
+    /// get_current_font_size() returns the size of the current font, or 0.0 if no
+    /// font is set.
+    ///
+    /// API: HPDF_Page_GetCurrentFontSize
+    ///
+    pub fn get_current_font_size(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetCurrentFontSize(self.page) }
+    }
+
+    /// get_trans_matrix() returns the current transformation matrix.
+    ///
+    /// API: HPDF_Page_GetTransMatrix
+    ///
+    pub fn get_trans_matrix(&self) -> TransMatrix {
+        TransMatrix::from(unsafe { hb::HPDF_Page_GetTransMatrix(self.page) })
+    }
+
+    /// get_text_matrix() returns the current text matrix.
+    ///
+    /// API: HPDF_Page_GetTextMatrix
+    ///
+    pub fn get_text_matrix(&self) -> TransMatrix {
+        TransMatrix::from(unsafe { hb::HPDF_Page_GetTextMatrix(self.page) })
+    }
+
+    /// get_line_width() returns the current line width.
+    ///
+    /// API: HPDF_Page_GetLineWidth
+    ///
+    pub fn get_line_width(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetLineWidth(self.page) }
+    }
+
+    /// get_line_cap() returns the current line cap style.
+    ///
+    /// API: HPDF_Page_GetLineCap
+    ///
+    pub fn get_line_cap(&self) -> LineCap {
+        LineCap::from(unsafe { hb::HPDF_Page_GetLineCap(self.page) })
+    }
+
+    /// get_line_join() returns the current line join style.
+    ///
+    /// API: HPDF_Page_GetLineJoin
+    ///
+    pub fn get_line_join(&self) -> LineJoin {
+        LineJoin::from(unsafe { hb::HPDF_Page_GetLineJoin(self.page) })
+    }
+
+    /// get_miter_limit() returns the current miter limit.
+    ///
+    /// API: HPDF_Page_GetMiterLimit
+    ///
+    pub fn get_miter_limit(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetMiterLimit(self.page) }
+    }
+
+    /// get_dash() returns the current dash pattern.
+    ///
+    /// API: HPDF_Page_GetDash
+    ///
+    pub fn get_dash(&self) -> DashMode {
+        DashMode::from(unsafe { hb::HPDF_Page_GetDash(self.page) })
+    }
+
+    /// get_flat() returns the current flatness tolerance.
+    ///
+    /// API: HPDF_Page_GetFlat
+    ///
+    pub fn get_flat(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetFlat(self.page) }
+    }
+
+    /// get_char_space() returns the current character spacing.
+    ///
+    /// API: HPDF_Page_GetCharSpace
+    ///
+    pub fn get_char_space(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetCharSpace(self.page) }
+    }
+
+    /// get_word_space() returns the current word spacing.
+    ///
+    /// API: HPDF_Page_GetWordSpace
+    ///
+    pub fn get_word_space(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetWordSpace(self.page) }
+    }
+
+    /// get_horizontal_scalling() returns the current horizontal scaling of text,
+    /// in percent.
+    ///
+    /// API: HPDF_Page_GetHorizontalScalling
+    ///
+    pub fn get_horizontal_scalling(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetHorizontalScalling(self.page) }
+    }
+
+    /// get_text_leading() returns the current line spacing of text.
+    ///
+    /// API: HPDF_Page_GetTextLeading
+    ///
+    pub fn get_text_leading(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetTextLeading(self.page) }
+    }
+
+    /// get_text_rendering_mode() returns the current text rendering mode.
+    ///
+    /// API: HPDF_Page_GetTextRenderingMode
+    ///
+    pub fn get_text_rendering_mode(&self) -> RenderingMode {
+        RenderingMode::from(unsafe { hb::HPDF_Page_GetTextRenderingMode(self.page) })
+    }
+
+    /// get_text_rise() returns the current text rise.
+    ///
+    /// API: HPDF_Page_GetTextRise
+    ///
+    pub fn get_text_rise(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetTextRise(self.page) }
+    }
+
+    /// get_rgb_fill() returns the current filling color as RGB.
+    ///
+    /// API: HPDF_Page_GetRGBFill
+    ///
+    pub fn get_rgb_fill(&self) -> RgbColor {
+        RgbColor::from(unsafe { hb::HPDF_Page_GetRGBFill(self.page) })
+    }
+
+    /// get_rgb_stroke() returns the current stroking color as RGB.
+    ///
+    /// API: HPDF_Page_GetRGBStroke
+    ///
+    pub fn get_rgb_stroke(&self) -> RgbColor {
+        RgbColor::from(unsafe { hb::HPDF_Page_GetRGBStroke(self.page) })
+    }
+
+    /// get_cmyk_fill() returns the current filling color as CMYK.
+    ///
+    /// API: HPDF_Page_GetCMYKFill
+    ///
+    pub fn get_cmyk_fill(&self) -> CmykColor {
+        CmykColor::from(unsafe { hb::HPDF_Page_GetCMYKFill(self.page) })
+    }
+
+    /// get_cmyk_stroke() returns the current stroking color as CMYK.
+    ///
+    /// API: HPDF_Page_GetCMYKStroke
+    ///
+    pub fn get_cmyk_stroke(&self) -> CmykColor {
+        CmykColor::from(unsafe { hb::HPDF_Page_GetCMYKStroke(self.page) })
+    }
+
+    /// get_gray_fill() returns the current filling color as gray level.
+    ///
+    /// API: HPDF_Page_GetGrayFill
+    ///
+    pub fn get_gray_fill(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetGrayFill(self.page) }
+    }
+
+    /// get_gray_stroke() returns the current stroking color as gray level.
+    ///
+    /// API: HPDF_Page_GetGrayStroke
+    ///
+    pub fn get_gray_stroke(&self) -> f32 {
+        unsafe { hb::HPDF_Page_GetGrayStroke(self.page) }
+    }
+
+    /// get_filling_color_space() returns the color space of the current filling
+    /// color, or None if it isn't a device color space.
+    ///
+    /// API: HPDF_Page_GetFillingColorSpace
+    ///
+    pub fn get_filling_color_space(&self) -> Option<ColorSpace> {
+        ColorSpace::from_hpdf_color_space(unsafe { hb::HPDF_Page_GetFillingColorSpace(self.page) })
+    }
+
+    /// get_stroking_color_space() returns the color space of the current stroking
+    /// color, or None if it isn't a device color space.
+    ///
+    /// API: HPDF_Page_GetStrokingColorSpace
+    ///
+    pub fn get_stroking_color_space(&self) -> Option<ColorSpace> {
+        ColorSpace::from_hpdf_color_space(unsafe { hb::HPDF_Page_GetStrokingColorSpace(self.page) })
+    }
+
+    /// get_gstate_depth() returns the nesting depth of the graphics state:
+    /// 1 at the start of the page, increased by each g_save() that isn't
+    /// restored yet.
+    ///
+    /// API: HPDF_Page_GetGStateDepth
+    ///
+    pub fn get_gstate_depth(&self) -> u32 {
+        unsafe { hb::HPDF_Page_GetGStateDepth(self.page) }
+    }
+
+    /// graphics_state() captures the current graphics state of the page.
+    ///
+    pub fn graphics_state(&self) -> GraphicsStateSnapshot<'doc> {
+        GraphicsStateSnapshot {
+            gmode: self.get_gmode(),
+            current_pos: self.get_current_pos(),
+            current_text_pos: self.get_current_text_pos(),
+            font: self.get_current_font(),
+            font_size: self.get_current_font_size(),
+            trans_matrix: self.get_trans_matrix(),
+            text_matrix: self.get_text_matrix(),
+            line_width: self.get_line_width(),
+            line_cap: self.get_line_cap(),
+            line_join: self.get_line_join(),
+            miter_limit: self.get_miter_limit(),
+            dash: self.get_dash(),
+            flat: self.get_flat(),
+            char_space: self.get_char_space(),
+            word_space: self.get_word_space(),
+            horizontal_scalling: self.get_horizontal_scalling(),
+            text_leading: self.get_text_leading(),
+            text_rendering_mode: self.get_text_rendering_mode(),
+            text_rise: self.get_text_rise(),
+            rgb_fill: self.get_rgb_fill(),
+            rgb_stroke: self.get_rgb_stroke(),
+            cmyk_fill: self.get_cmyk_fill(),
+            cmyk_stroke: self.get_cmyk_stroke(),
+            gray_fill: self.get_gray_fill(),
+            gray_stroke: self.get_gray_stroke(),
+            filling_color_space: self.get_filling_color_space(),
+            stroking_color_space: self.get_stroking_color_space(),
+            gstate_depth: self.get_gstate_depth(),
+        }
+    }
+
     /// set_rgb_fill() sets the filling color.
     ///
     /// Color values are between 0.0 and 1.0.
@@ -741,7 +1479,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetRGBFill(self.page, r, g, b) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetRGBFill", result)),
         }
     }
 
@@ -755,7 +1493,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetRGBStroke(self.page, r, g, b) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetRGBStroke", result)),
         }
     }
 
@@ -768,7 +1506,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetTextLeading(self.page, value) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetTextLeading", result)),
         }
     }
 
@@ -789,7 +1527,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetTextMatrix(self.page, a, b, c, d, x, y) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetTextMatrix", result)),
         }
     }
 
@@ -802,7 +1540,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetTextRise(self.page, value) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetTextRise", result)),
         }
     }
 
@@ -814,7 +1552,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_SetWordSpace(self.page, value) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_SetWordSpace", result)),
         }
     }
 
@@ -827,15 +1565,35 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ShowText(self.page, text.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_ShowText", result)),
+        }
+    }
+
+    /// create_destination() creates a destination on this page, which can be
+    /// used as the open action of the document or as the target of an outline.
+    ///
+    /// API: HPDF_Page_CreateDestination
+    ///
+    pub fn create_destination(
+        &self,
+        destination: Destination,
+    ) -> Result<PdfDestination<'doc>, HaruError> {
+        let dst = unsafe { hb::HPDF_Page_CreateDestination(self.page) };
+        if dst.is_null() {
+            return Err(self.error("HPDF_Page_CreateDestination", 0));
         }
+        let dst = PdfDestination { dst, doc: self.doc };
+        dst.set(destination)?;
+        Ok(dst)
     }
 
     /// draw_image() shows an image in one operation.
     ///
     /// API: HPDF_Page_DrawImage
     ///
-    pub fn draw_image(&self, image: &PdfImage, rect: &Rect) -> Result<&Self, HaruError> {
+    pub fn draw_image(&self, image: &PdfImage<'_>, rect: &Rect) -> Result<&Self, HaruError> {
+        self.doc
+            .ensure_same(image.doc, "HPDF_Page_DrawImage", hb::HPDF_INVALID_IMAGE)?;
         let result = unsafe {
             hb::HPDF_Page_DrawImage(
                 self.page,
@@ -848,7 +1606,7 @@ impl PdfPage {
         };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_DrawImage", result)),
         }
     }
 
@@ -856,12 +1614,15 @@ impl PdfPage {
     ///
     pub fn draw_image_fit(
         &self,
-        image: &PdfImage,
+        image: &PdfImage<'_>,
         rect: Rect,
         fit: ImageFit,
         align_h: haru_types::ImageAlign,
         align_v: haru_types::ImageAlign,
     ) -> Result<&Self, HaruError> {
+        self.doc
+            .ensure_same(image.doc, "HPDF_Page_DrawImage", hb::HPDF_INVALID_IMAGE)?;
+
         // get the raw size of the input image
 
         let img_width = image.get_width()? as f32;
@@ -955,7 +1716,7 @@ impl PdfPage {
 
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_DrawImage", result)),
         }
     }
 
@@ -969,7 +1730,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_ShowTextNextLine(self.page, text.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_ShowTextNextLine", result)),
         }
     }
 
@@ -991,7 +1752,7 @@ impl PdfPage {
         };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_ShowTextNextLineEx", result)),
         }
     }
 
@@ -1003,7 +1764,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_Stroke(self.page) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_Stroke", result)),
         }
     }
 
@@ -1016,7 +1777,7 @@ impl PdfPage {
         let result = unsafe { hb::HPDF_Page_TextOut(self.page, x, y, text.as_ptr()) };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_TextOut", result)),
         }
     }
 
@@ -1048,7 +1809,241 @@ impl PdfPage {
         };
         match result {
             0 => Ok(self),
-            _ => Err(HaruError::from(result)),
+            _ => Err(self.error("HPDF_Page_TextRect", result)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haru_types::HaruErrorKind;
+
+    #[test]
+    fn test_page_size_and_rotation() {
+        let doc = PdfDocument::new();
+        let page = doc
+            .add_page_with(PageSize::A4, PageDirection::Landscape)
+            .unwrap();
+        assert_eq!(page.get_width().round(), 842.0);
+        assert_eq!(page.get_height().round(), 595.0);
+
+        page.set_size(PageSize::Letter, PageDirection::Portrait)
+            .unwrap();
+        assert_eq!(page.get_width(), 612.0);
+        assert_eq!(page.get_height(), 792.0);
+
+        page.set_rotate(90).unwrap();
+        page.set_zoom(1.5).unwrap();
+
+        let err = page.set_rotate(45).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageInvalidRotateValue);
+        assert_eq!(err.api(), Some("HPDF_Page_SetRotate"));
+    }
+
+    #[test]
+    fn test_page_boundaries() {
+        let doc = PdfDocument::new();
+        let page = doc
+            .add_page_with(PageSize::A4, PageDirection::Portrait)
+            .unwrap();
+        assert!(page.get_boundary(BoundaryKind::TrimBox).is_none());
+
+        let bleed = Rect::new(20.0, 20.0, 555.0, 801.0);
+        let trim = Rect::new(29.0, 29.0, 537.0, 783.0);
+        page.set_boundary(BoundaryKind::BleedBox, &bleed).unwrap();
+        page.set_boundary(BoundaryKind::TrimBox, &trim).unwrap();
+        assert_eq!(page.get_boundary(BoundaryKind::BleedBox), Some(bleed));
+        assert_eq!(page.get_boundary(BoundaryKind::TrimBox), Some(trim));
+
+        // The trim box must lie inside the bleed box.
+        let err = page
+            .set_boundary(BoundaryKind::TrimBox, &Rect::new(10.0, 10.0, 100.0, 100.0))
+            .unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageInvalidBoundary);
+        assert_eq!(page.get_boundary(BoundaryKind::TrimBox), Some(trim));
+
+        // The crop box can't cut into the bleed box.
+        assert!(page
+            .set_boundary(BoundaryKind::CropBox, &Rect::new(25.0, 25.0, 500.0, 500.0))
+            .is_err());
+        assert!(page
+            .set_boundary(BoundaryKind::MediaBox, &Rect::new(0.0, 0.0, 600.0, 850.0))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_dash() {
+        let doc = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        page.set_dash(&[3.0, 1.5], 1.0)
+            .unwrap()
+            .set_dash_pattern(DashPattern::DashDot)
+            .unwrap()
+            .set_dash(&[], 0.0)
+            .unwrap()
+            .set_flat(2.0)
+            .unwrap();
+
+        let err = page.set_dash(&[1.0; 9], 0.0).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageOutOfRange);
+        assert!(page.set_dash(&[1.0, 0.0], 0.0).is_err());
+        assert!(page.set_dash(&[1.0, 101.0], 0.0).is_err());
+        assert!(page.set_dash(&[], 1.0).is_err());
+        assert!(page.set_dash(&[1.0], -1.0).is_err());
+        page.set_dash(&[1.0; 8], 0.0).unwrap();
+
+        let err = page.set_flat(101.0).unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageOutOfRange);
+        assert!(page.set_flat(-1.0).is_err());
+        assert!(page.set_flat(f32::NAN).is_err());
+        assert!(doc.last_error().is_none());
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        assert!(pdf.contains("[6 3 1 3 ] 0 d"));
+        assert!(pdf.contains("2 i"));
+    }
+
+    #[test]
+    fn test_graphics_state() {
+        let doc = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        assert_eq!(page.get_gmode(), GMode::PageDescription);
+        assert!(page.get_current_font().is_none());
+        assert_eq!(page.get_current_pos(), None);
+
+        page.move_to(10.0, 20.0)
+            .unwrap()
+            .line_to(30.0, 40.0)
+            .unwrap();
+        assert_eq!(page.get_gmode(), GMode::PathObject);
+        assert_eq!(page.get_current_pos(), Some(Point { x: 30.0, y: 40.0 }));
+        page.stroke().unwrap();
+
+        let font = doc.get_font("Helvetica", None).unwrap();
+        page.g_save()
+            .unwrap()
+            .set_line_width(2.5)
+            .unwrap()
+            .set_dash(&[3.0, 1.0], 0.5)
+            .unwrap()
+            .set_rgb_fill(1.0, 0.0, 0.5)
+            .unwrap()
+            .set_text_leading(14.0)
+            .unwrap()
+            .set_font_and_size(font, 12.0)
+            .unwrap();
+        page.begin_text()
+            .unwrap()
+            .move_text_pos(50.0, 700.0)
+            .unwrap()
+            .show_text("Hello")
+            .unwrap();
+
+        let state = page.graphics_state();
+        assert_eq!(state.gmode, GMode::TextObject);
+        let pos = state.current_text_pos.unwrap();
+        assert_eq!(
+            pos.x,
+            50.0 + font.text_width("Hello").width as f32 * 12.0 / 1000.0
+        );
+        assert_eq!(pos.y, 700.0);
+        assert_eq!(state.font.unwrap().get_font_name(), "Helvetica");
+        assert_eq!(state.font_size, 12.0);
+        assert_eq!(state.line_width, 2.5);
+        assert_eq!(state.dash.pattern, vec![3.0, 1.0]);
+        assert_eq!(state.dash.phase, 0.5);
+        assert_eq!(
+            state.rgb_fill,
+            RgbColor {
+                r: 1.0,
+                g: 0.0,
+                b: 0.5
+            }
+        );
+        assert_eq!(state.filling_color_space, Some(ColorSpace::DeviceRGB));
+        assert_eq!(state.text_leading, 14.0);
+        assert_eq!(state.gstate_depth, 2);
+        assert_eq!(state.trans_matrix.a, 1.0);
+
+        page.end_text().unwrap().g_restore().unwrap();
+        assert_eq!(page.get_current_text_pos(), None);
+        assert_eq!(page.get_gstate_depth(), 1);
+        assert_eq!(page.get_line_width(), 1.0);
+        assert!(page.get_dash().pattern.is_empty());
+    }
+
+    #[test]
+    fn test_page_guards() {
+        let doc = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        let font = doc.get_font("Helvetica", None).unwrap();
+
+        let draw = || -> Result<(), HaruError> {
+            let state = page.saved_state()?;
+            state
+                .set_line_width(3.0)?
+                .move_to(10.0, 10.0)?
+                .line_to(20.0, 20.0)?
+                .stroke()?;
+            let text = state.text_block()?;
+            text.set_font_and_size(font, 12.0)?
+                .move_text_pos(50.0, 50.0)?
+                .show_text("guarded")?;
+            assert_eq!(page.get_gstate_depth(), 2);
+            // The text object can't be nested, so this fails early.
+            let _nested = state.text_block()?;
+            Ok(())
+        };
+        let err = draw().unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageInvalidGmode);
+        // The guards clean up, but keep the error.
+        assert!(doc.save_to_bytes().is_err());
+        doc.reset_error();
+        assert_eq!(page.get_gmode(), GMode::PageDescription);
+        assert_eq!(page.get_gstate_depth(), 1);
+        assert_eq!(page.get_line_width(), 1.0);
+
+        // An unfinished path is ended before restoring.
+        {
+            let state = page.saved_state().unwrap();
+            state
+                .move_to(0.0, 0.0)
+                .unwrap()
+                .line_to(10.0, 10.0)
+                .unwrap();
+        }
+        assert_eq!(page.get_gstate_depth(), 1);
+
+        // An error raised meanwhile survives the cleanup, both in libharu's
+        // error record and as last_error().
+        {
+            let state = page.saved_state().unwrap();
+            let text = state.text_block().unwrap();
+            text.set_font_and_size(font, 12.0).unwrap();
+            assert!(doc.get_font("NoSuchFont", None).is_err());
         }
+        let status = unsafe { hb::HPDF_GetError(doc.doc) };
+        assert_eq!(status, hb::HPDF_INVALID_FONT_NAME as u64);
+        let last = doc.last_error().unwrap();
+        assert_eq!(last.kind(), HaruErrorKind::InvalidFontName);
+        doc.reset_error();
+        assert_eq!(page.get_gmode(), GMode::PageDescription);
+        assert_eq!(page.get_gstate_depth(), 1);
+
+        let state = page.saved_state().unwrap();
+        state.g_save().unwrap();
+        let page = state.restore().unwrap();
+        assert_eq!(page.get_gstate_depth(), 1);
+        let text = page.text_block().unwrap();
+        text.end_text().unwrap();
+        text.end().unwrap();
+
+        let bytes = doc.save_to_bytes().unwrap();
+        let pdf = String::from_utf8_lossy(&bytes);
+        let count = |op: &str| pdf.lines().filter(|line| *line == op).count();
+        assert_eq!((count("q"), count("Q")), (5, 5));
+        assert_eq!((count("BT"), count("ET")), (3, 3));
     }
 }
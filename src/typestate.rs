@@ -0,0 +1,337 @@
+// Copyright (c) 2023-2024 Bastian Bense
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+// Contact: Bastian Bense, bb@neosw.de
+
+//! Opt-in typestate layer over [`PdfPage`].
+//!
+//! libharu only accepts an operator in some graphics modes, and rejects it
+//! otherwise at runtime with `PageInvalidGmode`. The wrappers in this module
+//! track the graphics mode in the type instead: every operator consumes the
+//! current state and returns the state libharu is in afterwards.
+//! [`PdfPage::page_description()`] enters the layer and
+//! [`PageDescription::into_page()`] leaves it again.
+//!
+//! ```
+//! use libharu_ng::prelude::*;
+//!
+//! let doc = PdfDocument::new();
+//! let font = doc.get_font("Helvetica", None).unwrap();
+//! let page = doc.add_page().unwrap().page_description().unwrap();
+//! let page = page
+//!     .move_to(10.0, 10.0).unwrap()
+//!     .line_to(100.0, 100.0).unwrap()
+//!     .stroke().unwrap();
+//! let page = page
+//!     .begin_text().unwrap()
+//!     .set_font_and_size(font, 12.0).unwrap()
+//!     .show_text("Hello").unwrap()
+//!     .end_text().unwrap();
+//! page.into_page();
+//! ```
+//!
+//! Text can only be shown inside a text object:
+//!
+//! ```compile_fail
+//! use libharu_ng::prelude::*;
+//!
+//! let doc = PdfDocument::new();
+//! let page = doc.add_page().unwrap().page_description().unwrap();
+//! page.show_text("Hello");
+//! ```
+
+use crate::ext_gstate::PdfExtGState;
+use crate::font::PdfFont;
+use crate::haru_bindings as hb;
+use crate::haru_types::{
+    DashPattern, GMode, HaruError, ImageAlign, ImageFit, LineCap, LineJoin, Rect, RenderingMode,
+    TextAlign,
+};
+use crate::image::PdfImage;
+use crate::page::{GraphicsStateSnapshot, PdfPage};
+
+/// Generates methods forwarding to the `PdfPage` operator of the same name,
+/// returning the page in the given state.
+macro_rules! operators {
+    ($next:ident: $(fn $name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            #[doc = concat!("See [`PdfPage::", stringify!($name), "()`].")]
+            pub fn $name(self, $($arg: $ty),*) -> Result<$next<'doc>, HaruError> {
+                self.page.$name($($arg),*)?;
+                Ok($next { page: self.page })
+            }
+        )*
+    };
+}
+
+/// Generates the graphics and text state operators, valid both in page
+/// description mode and in a text object.
+macro_rules! state_operators {
+    ($state:ident) => {
+        operators! { $state:
+            fn set_line_width(line_width: f32);
+            fn set_line_cap(line_cap: LineCap);
+            fn set_line_join(line_join: LineJoin);
+            fn set_miter_limit(miter_limit: f32);
+            fn set_dash(pattern: &[f32], phase: f32);
+            fn set_dash_pattern(pattern: DashPattern);
+            fn set_flat(flatness: f32);
+            fn set_char_space(value: f32);
+            fn set_word_space(value: f32);
+            fn set_horizontal_scalling(value: f32);
+            fn set_text_leading(value: f32);
+            fn set_font_and_size(font: PdfFont<'_>, size: f32);
+            fn set_text_rendering_mode(mode: RenderingMode);
+            fn set_text_rise(value: f32);
+            fn set_gray_fill(gray: f32);
+            fn set_gray_stroke(gray: f32);
+            fn set_rgb_fill(r: f32, g: f32, b: f32);
+            fn set_rgb_stroke(r: f32, g: f32, b: f32);
+            fn set_cmyk_fill(c: f32, m: f32, y: f32, k: f32);
+            fn set_cmyk_stroke(c: f32, m: f32, y: f32, k: f32);
+        }
+    };
+}
+
+/// Generates the operators painting or discarding the current path.
+macro_rules! painting_operators {
+    () => {
+        operators! { PageDescription:
+            fn stroke();
+            fn close_path_stroke();
+            fn fill();
+            fn eo_fill();
+            fn fill_stroke();
+            fn eo_fill_stroke();
+            fn close_path_fill_stroke();
+            fn close_path_eofill_stroke();
+            fn end_path();
+        }
+    };
+}
+
+impl<'doc> PdfPage<'doc> {
+    /// page_description() enters the typestate layer. It fails with
+    /// `PageInvalidGmode` unless the page is in page description mode.
+    ///
+    pub fn page_description(&self) -> Result<PageDescription<'doc>, HaruError> {
+        match self.get_gmode() {
+            GMode::PageDescription => Ok(PageDescription { page: *self }),
+            _ => {
+                Err(HaruError::from(hb::HPDF_PAGE_INVALID_GMODE as u64)
+                    .with_api("HPDF_Page_GetGMode"))
+            }
+        }
+    }
+}
+
+/// A page in page description mode, outside of any path or text object.
+///
+#[derive(Debug)]
+pub struct PageDescription<'doc> {
+    page: PdfPage<'doc>,
+}
+
+impl<'doc> PageDescription<'doc> {
+    /// into_page() leaves the typestate layer.
+    ///
+    pub fn into_page(self) -> PdfPage<'doc> {
+        self.page
+    }
+
+    /// See [`PdfPage::graphics_state()`].
+    pub fn graphics_state(&self) -> GraphicsStateSnapshot<'doc> {
+        self.page.graphics_state()
+    }
+
+    state_operators!(PageDescription);
+
+    operators! { PageDescription:
+        fn g_save();
+        fn g_restore();
+        fn concat(a: f32, b: f32, c: f32, d: f32, x: f32, y: f32);
+        fn mtrx_rotate(degrees: f32);
+        fn mtrx_translate(x: f32, y: f32);
+        fn set_ext_gstate(gstate: &PdfExtGState<'_>);
+        fn draw_image(image: &PdfImage<'_>, rect: &Rect);
+        fn draw_image_fit(
+            image: &PdfImage<'_>,
+            rect: Rect,
+            fit: ImageFit,
+            align_h: ImageAlign,
+            align_v: ImageAlign
+        );
+    }
+
+    operators! { PathObject:
+        fn move_to(x: f32, y: f32);
+        fn rectangle(x: f32, y: f32, width: f32, height: f32);
+        fn circle(x: f32, y: f32, radius: f32);
+        fn ellipse(x: f32, y: f32, xray: f32, yray: f32);
+        fn arc(x: f32, y: f32, radius: f32, ang1: f32, ang2: f32);
+    }
+
+    operators! { TextObject:
+        fn begin_text();
+    }
+}
+
+/// A page inside a path object, started by one of the path construction
+/// operators. The path ends with a painting operator.
+///
+#[derive(Debug)]
+#[must_use = "the path object is left open until it is painted or ended"]
+pub struct PathObject<'doc> {
+    page: PdfPage<'doc>,
+}
+
+impl<'doc> PathObject<'doc> {
+    /// See [`PdfPage::graphics_state()`].
+    pub fn graphics_state(&self) -> GraphicsStateSnapshot<'doc> {
+        self.page.graphics_state()
+    }
+
+    operators! { PathObject:
+        fn move_to(x: f32, y: f32);
+        fn line_to(x: f32, y: f32);
+        fn curve_to(x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32);
+        fn curve_to2(x2: f32, y2: f32, x3: f32, y3: f32);
+        fn curve_to3(x1: f32, y1: f32, x3: f32, y3: f32);
+        fn close_path();
+        fn rectangle(x: f32, y: f32, width: f32, height: f32);
+        fn circle(x: f32, y: f32, radius: f32);
+        fn ellipse(x: f32, y: f32, xray: f32, yray: f32);
+        fn arc(x: f32, y: f32, radius: f32, ang1: f32, ang2: f32);
+    }
+
+    operators! { ClippingPath:
+        fn clip();
+        fn eo_clip();
+    }
+
+    painting_operators!();
+}
+
+/// A page after [`PathObject::clip()`] or [`PathObject::eo_clip()`]. The
+/// clipping path takes effect with the following painting operator.
+///
+#[derive(Debug)]
+#[must_use = "the clipping path is left open until it is painted or ended"]
+pub struct ClippingPath<'doc> {
+    page: PdfPage<'doc>,
+}
+
+impl<'doc> ClippingPath<'doc> {
+    /// See [`PdfPage::graphics_state()`].
+    pub fn graphics_state(&self) -> GraphicsStateSnapshot<'doc> {
+        self.page.graphics_state()
+    }
+
+    painting_operators!();
+}
+
+/// A page inside a text object, started by
+/// [`PageDescription::begin_text()`].
+///
+#[derive(Debug)]
+#[must_use = "the text object is left open until end_text() is called"]
+pub struct TextObject<'doc> {
+    page: PdfPage<'doc>,
+}
+
+impl<'doc> TextObject<'doc> {
+    /// See [`PdfPage::graphics_state()`].
+    pub fn graphics_state(&self) -> GraphicsStateSnapshot<'doc> {
+        self.page.graphics_state()
+    }
+
+    state_operators!(TextObject);
+
+    operators! { TextObject:
+        fn move_text_pos(x: f32, y: f32);
+        fn move_text_pos2(x: f32, y: f32);
+        fn move_to_next_line();
+        fn set_text_matrix(a: f32, b: f32, c: f32, d: f32, x: f32, y: f32);
+        fn show_text(text: &str);
+        fn show_text_next_line(text: &str);
+        fn show_text_next_line_ex(word_space: f32, char_space: f32, text: &str);
+        fn text_out(x: f32, y: f32, text: &str);
+        fn text_rect(
+            left: f32,
+            top: f32,
+            right: f32,
+            bottom: f32,
+            text: &str,
+            align: TextAlign
+        );
+    }
+
+    operators! { PageDescription:
+        fn end_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::PdfDocument;
+    use crate::haru_types::HaruErrorKind;
+
+    #[test]
+    fn test_typestate() {
+        let doc = PdfDocument::new();
+        let page = doc.add_page().unwrap();
+        let font = doc.get_font("Helvetica", None).unwrap();
+
+        let draw = || -> Result<PdfPage<'_>, HaruError> {
+            let path = page
+                .page_description()?
+                .set_line_width(2.0)?
+                .move_to(10.0, 10.0)?
+                .line_to(50.0, 50.0)?;
+            assert_eq!(path.graphics_state().gmode, GMode::PathObject);
+            let clip = path.close_path()?.rectangle(0.0, 0.0, 20.0, 20.0)?.clip()?;
+            assert_eq!(clip.graphics_state().gmode, GMode::ClippingPath);
+            let text = clip
+                .end_path()?
+                .g_save()?
+                .begin_text()?
+                .set_font_and_size(font, 12.0)?
+                .move_text_pos(20.0, 20.0)?
+                .show_text("typestate")?;
+            assert_eq!(text.graphics_state().gmode, GMode::TextObject);
+            Ok(text
+                .end_text()?
+                .g_restore()?
+                .circle(30.0, 30.0, 5.0)?
+                .fill()?
+                .into_page())
+        };
+        let page = draw().unwrap();
+        assert_eq!(page.get_gmode(), GMode::PageDescription);
+        assert_eq!(page.get_line_width(), 2.0);
+
+        page.begin_text().unwrap();
+        let err = page.page_description().unwrap_err();
+        assert_eq!(err.kind(), HaruErrorKind::PageInvalidGmode);
+        page.end_text().unwrap();
+        assert!(page.page_description().is_ok());
+    }
+}